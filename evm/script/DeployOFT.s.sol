@@ -5,22 +5,27 @@ import "forge-std/Script.sol";
 import "../src/MyOFT.sol";
 
 contract DeployOFT is Script {
-    function run(address _lzEndpoint) external returns (address) {
+    function run(
+        address _lzEndpoint,
+        string memory _name,
+        string memory _symbol,
+        address _delegate
+    ) external returns (address) {
         uint256 deployerPrivateKey = vm.envUint("PRIVATE_KEY");
         vm.startBroadcast(deployerPrivateKey);
 
         MyOFT oft = new MyOFT(
-            "Synthetix Solana Token", 
-            "SOL-SYN", 
-            _lzEndpoint, 
-            vm.addr(deployerPrivateKey)
+            _name,
+            _symbol,
+            _lzEndpoint,
+            _delegate == address(0) ? vm.addr(deployerPrivateKey) : _delegate
         );
 
         vm.stopBroadcast();
         
-        // CRITICAL: This specific log format is parsed by Rust
+        // Manual fallback; oft-cli deploys the compiled artifact directly
         console.log("DEPLOYED_ADDR:", address(oft));
         
         return address(oft);
     }
-}
\ No newline at end of file
+}
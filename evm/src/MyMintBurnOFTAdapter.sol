@@ -0,0 +1,16 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.22;
+
+import { MintBurnOFTAdapter } from "@layerzerolabs/oft-evm/contracts/MintBurnOFTAdapter.sol";
+import { IMintableBurnable } from "@layerzerolabs/oft-evm/contracts/interfaces/IMintableBurnable.sol";
+import { Ownable } from "@openzeppelin/contracts/access/Ownable.sol";
+
+/// Burns and mints an existing ERC20 through its IMintableBurnable minter.
+contract MyMintBurnOFTAdapter is MintBurnOFTAdapter {
+    constructor(
+        address _token,
+        IMintableBurnable _minterBurner,
+        address _lzEndpoint,
+        address _delegate
+    ) MintBurnOFTAdapter(_token, _minterBurner, _lzEndpoint, _delegate) Ownable(_delegate) {}
+}
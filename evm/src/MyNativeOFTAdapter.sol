@@ -0,0 +1,14 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.22;
+
+import { NativeOFTAdapter } from "@layerzerolabs/oft-evm/contracts/NativeOFTAdapter.sol";
+import { Ownable } from "@openzeppelin/contracts/access/Ownable.sol";
+
+/// Locks and unlocks the chain's native gas token.
+contract MyNativeOFTAdapter is NativeOFTAdapter {
+    constructor(
+        uint8 _localDecimals,
+        address _lzEndpoint,
+        address _delegate
+    ) NativeOFTAdapter(_localDecimals, _lzEndpoint, _delegate) Ownable(_delegate) {}
+}
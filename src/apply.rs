@@ -0,0 +1,341 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::str::FromStr;
+use solana_sdk::pubkey::Pubkey;
+use anyhow::Result;
+use crate::deploy::Deployed;
+use crate::export::{Exporter, Submit};
+use crate::manifest::{ChainKind, Manifest};
+use crate::state::{ChainDeployment, DeploymentState, PeerRecord};
+use crate::{evm_ops, preflight, solana_ops};
+
+/// Decimals preflight over every chain, using what is already deployed where `state` has it.
+async fn check_decimals(manifest: &Manifest, state: &DeploymentState) -> Result<()> {
+    let mut chains = Vec::new();
+    for chain in &manifest.chains {
+        let decimals = match &chain.kind {
+            ChainKind::Solana { .. } => {
+                let profile = chain.solana_profile().expect("solana chain");
+                let solana_oft = manifest.token.solana_oft();
+                let pda = solana_oft.recorded_pda(state, &chain.name)?.map(|pda| Pubkey::from_str(&pda)).transpose()?;
+                solana_ops::oft_decimals(&profile, &solana_oft, pda.as_ref())?
+            }
+            ChainKind::Evm { contract, .. } => {
+                let profile = chain.evm_profile().expect("evm chain");
+                let recorded = state.evm_oft(&chain.name, &manifest.token.oft_params(contract).spec())?;
+                evm_ops::oft_decimals(&profile, contract, recorded.as_deref()).await?
+            }
+        };
+        chains.push((chain.name.clone(), decimals));
+    }
+    preflight::check_decimals(&chains)
+}
+
+/// Deploys every chain in the manifest, wires each declared peer edge, then hands the EVM
+/// OFTs to `token.delegate`.
+/// Steps already recorded in `state` are skipped. With an `exporter`, the chains must already
+/// be deployed and the wiring is exported for the multisig instead of signed.
+pub async fn run(manifest: &Manifest, state: &mut DeploymentState, state_path: &Path, exporter: &mut Option<Exporter>) -> Result<()> {
+    println!("📜 Applying manifest: {} chain(s), {} peer link(s)", manifest.chains.len(), manifest.peers.len());
+
+    check_decimals(manifest, state).await?;
+    let mut deployed: HashMap<&str, Deployed> = HashMap::new();
+
+    for chain in &manifest.chains {
+        println!("\n⛓️  [{}]", chain.name);
+        let result = match &chain.kind {
+            ChainKind::Solana { endpoint, eid, deployer_key, .. } => {
+                println!("   Endpoint program: {} (EID {})", endpoint, eid);
+                let solana_oft = manifest.token.solana_oft();
+                if let Some(pda) = solana_oft.recorded_pda(state, &chain.name)? {
+                    println!("⏭️  Already deployed: {}", pda);
+                    Deployed::Solana(Pubkey::from_str(&pda)?)
+                } else if exporter.is_some() {
+                    anyhow::bail!("{} is not deployed yet; deploy it with a signed `apply` before exporting", chain.name);
+                } else {
+                    let profile = chain.solana_profile().expect("solana chain");
+                    let (mint, pda, sig) = solana_ops::init_oft(&profile, deployer_key, &solana_oft).await?;
+                    state.chains.insert(chain.name.clone(), ChainDeployment::Solana {
+                        mint: mint.to_string(),
+                        oft_pda: pda.to_string(),
+                        signature: sig.to_string(),
+                        spec: Some(solana_oft.spec()),
+                    });
+                    state.save(state_path)?;
+                    Deployed::Solana(pda)
+                }
+            }
+            ChainKind::Evm { chain_id, endpoint, eid, deployer_key, contract, .. } => {
+                println!("   Endpoint: {} (EID {})", endpoint, eid);
+                let params = manifest.token.oft_params(contract);
+                if let Some(addr) = state.evm_oft(&chain.name, &params.spec())? {
+                    println!("⏭️  Already deployed: {}", addr);
+                    Deployed::Evm(addr)
+                } else if exporter.is_some() {
+                    anyhow::bail!("{} is not deployed yet; deploy it with a signed `apply` before exporting", chain.name);
+                } else {
+                    let profile = chain.evm_profile().expect("evm chain");
+                    let (addr, tx_hash) = evm_ops::deploy_evm_oft(&profile, deployer_key, &params).await?;
+                    println!("📝 Captured EVM Address: {}", addr);
+                    state.chains.insert(chain.name.clone(), ChainDeployment::Evm {
+                        chain_id: *chain_id,
+                        address: addr.clone(),
+                        tx_hash: Some(tx_hash),
+                        spec: Some(params.spec()),
+                    });
+                    state.save(state_path)?;
+                    Deployed::Evm(addr)
+                }
+            }
+        };
+        deployed.insert(&chain.name, result);
+    }
+
+    for peer in &manifest.peers {
+        // validate() guarantees both ends exist
+        let from = manifest.chain(&peer.from).expect("validated chain");
+        let to = manifest.chain(&peer.to).expect("validated chain");
+        let remote_eid = to.eid();
+        let remote_bytes = deployed[to.name.as_str()].to_bytes32()?;
+        let peer_hex = format!("0x{}", hex::encode(remote_bytes));
+
+        println!("\n🔗 {} -> {} (remote EID {})", from.name, to.name, remote_eid);
+        if state.has_peer(&from.name, remote_eid, &peer_hex) {
+            println!("⏭️  Peer already set");
+        } else {
+            let tx = match (&from.kind, &deployed[from.name.as_str()]) {
+                (ChainKind::Solana { deployer_key, .. }, Deployed::Solana(pda)) => {
+                    let profile = from.solana_profile().expect("solana chain");
+                    let mut submit = Submit::new(exporter, || Ok(deployer_key.clone()))?;
+                    let sig = solana_ops::set_peer_solana(&profile, &mut submit, &from.name, *pda, remote_eid, remote_bytes).await?;
+                    sig.map(|sig| sig.to_string())
+                }
+                (ChainKind::Evm { deployer_key, .. }, Deployed::Evm(addr)) => {
+                    let profile = from.evm_profile().expect("evm chain");
+                    let mut submit = Submit::new(exporter, || Ok(deployer_key.clone()))?;
+                    evm_ops::set_peer_evm(&profile, &mut submit, addr, remote_eid, remote_bytes).await?
+                }
+                _ => unreachable!("deployment kind always matches chain kind"),
+            };
+            // Exported peers are not recorded: they only land once the multisig executes them
+            if tx.is_some() {
+                state.record_peer(PeerRecord {
+                    from: from.name.clone(),
+                    to: to.name.clone(),
+                    remote_eid,
+                    peer: peer_hex,
+                    tx,
+                });
+                state.save(state_path)?;
+            }
+        }
+
+        // Read back and compared on-chain, so reruns only send what changed
+        if let Some(spec) = &peer.enforced_options {
+            match (&from.kind, &deployed[from.name.as_str()]) {
+                (ChainKind::Solana { deployer_key, .. }, Deployed::Solana(pda)) => {
+                    let profile = from.solana_profile().expect("solana chain");
+                    let mut submit = Submit::new(exporter, || Ok(deployer_key.clone()))?;
+                    solana_ops::set_enforced_options_solana(&profile, &mut submit, &from.name, *pda, remote_eid, spec).await?;
+                }
+                (ChainKind::Evm { deployer_key, .. }, Deployed::Evm(addr)) => {
+                    let profile = from.evm_profile().expect("evm chain");
+                    let mut submit = Submit::new(exporter, || Ok(deployer_key.clone()))?;
+                    evm_ops::set_enforced_options_evm(&profile, &mut submit, addr, remote_eid, spec).await?;
+                }
+                _ => unreachable!("deployment kind always matches chain kind"),
+            }
+        }
+        // A peer without `rate_limit` is unlimited, so limiters set earlier get cleared
+        if let (ChainKind::Solana { deployer_key, .. }, Deployed::Solana(pda)) = (&from.kind, &deployed[from.name.as_str()]) {
+            let profile = from.solana_profile().expect("solana chain");
+            let limits = peer.rate_limit.clone().unwrap_or_default();
+            let mut submit = Submit::new(exporter, || Ok(deployer_key.clone()))?;
+            solana_ops::set_rate_limits_solana(&profile, &mut submit, &from.name, *pda, remote_eid, &limits).await?;
+        }
+    }
+
+    // Wiring is `onlyOwner`, so the deployer keeps the EVM OFTs until every peer is done
+    for chain in &manifest.chains {
+        if let (ChainKind::Evm { deployer_key, contract, .. }, Deployed::Evm(addr)) = (&chain.kind, &deployed[chain.name.as_str()]) {
+            let profile = chain.evm_profile().expect("evm chain");
+            let mut submit = Submit::new(exporter, || Ok(deployer_key.clone()))?;
+            evm_ops::hand_over_evm(&profile, &mut submit, addr, &manifest.token.oft_params(contract)).await?;
+        }
+    }
+
+    println!("\n🚀 Manifest applied! State saved to {}", state_path.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use base64::Engine;
+    use serde_json::{json, Value};
+    use crate::export::{Check, Exported};
+    use crate::idl::{self, Idl};
+    use crate::oft_client::{OFTType, DEFAULT_OFT_PROGRAM_ID, TOKEN_PROGRAM_ID};
+
+    /// JSON-RPC endpoint serving `accounts` (address -> owner, data) and no EVM code.
+    fn serve(accounts: HashMap<String, (Pubkey, Vec<u8>)>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(&stream);
+                let mut length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line == "\r\n" {
+                        break;
+                    }
+                    if let Some((name, value)) = line.split_once(':') {
+                        if name.eq_ignore_ascii_case("content-length") {
+                            length = value.trim().parse().unwrap();
+                        }
+                    }
+                }
+                let mut body = vec![0; length];
+                reader.read_exact(&mut body).unwrap();
+                let request: Value = serde_json::from_slice(&body).unwrap();
+                let result = match request["method"].as_str().unwrap() {
+                    "getAccountInfo" => {
+                        let value = accounts.get(request["params"][0].as_str().unwrap()).map(|(owner, data)| json!({
+                            "data": [base64::engine::general_purpose::STANDARD.encode(data), "base64"],
+                            "executable": false,
+                            "lamports": 1_000_000,
+                            "owner": owner.to_string(),
+                            "rentEpoch": 0,
+                            "space": data.len(),
+                        }));
+                        json!({ "context": { "slot": 1 }, "value": value })
+                    }
+                    "getVersion" => json!({ "solana-core": "1.18.26", "feature-set": 0 }),
+                    "eth_getCode" => json!("0x"),
+                    _ => Value::Null,
+                };
+                let response = match result {
+                    Value::Null => json!({ "jsonrpc": "2.0", "id": request["id"], "error": { "code": -32601, "message": "Method not found" } }),
+                    result => json!({ "jsonrpc": "2.0", "id": request["id"], "result": result }),
+                }
+                .to_string();
+                write!(stream, "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", response.len(), response).unwrap();
+            }
+        });
+        url
+    }
+
+    const ENDPOINT: &str = "76y77prsiCMvXMjuoZ5VRrhG5qYBrUMYTE5WgHqgjEn6";
+
+    /// A Solana edge to an EVM chain with both enforced options and an outbound limit.
+    fn manifest(url: &str) -> Manifest {
+        toml::from_str(&format!(
+            r#"
+            [token]
+            create_mint = {{ decimals = 9 }}
+            name = "Token"
+            symbol = "TKN"
+
+            [[chains]]
+            name = "solana"
+            kind = "solana"
+            rpc_url = "{url}"
+            endpoint = "{ENDPOINT}"
+            eid = 30168
+            deployer_key = {{ env = "UNUSED_KEY" }}
+
+            [[chains]]
+            name = "evm"
+            kind = "evm"
+            chain_id = 1
+            rpc_url = "{url}"
+            endpoint = "0x1a44076050125825900e736c501f859c50fE728c"
+            eid = 30101
+            deployer_key = {{ env = "UNUSED_KEY" }}
+
+            [[peers]]
+            from = "solana"
+            to = "evm"
+            enforced_options = {{ lz_receive_gas = 200000 }}
+            rate_limit = {{ outbound = {{ capacity = 1000, refill_per_second = 10 }} }}
+            "#
+        ))
+        .unwrap()
+    }
+
+    /// The OFT store of a 9-decimal native OFT and its mint; no peer config exists yet.
+    fn accounts(oft_store: &Pubkey, admin: &Pubkey) -> HashMap<String, (Pubkey, Vec<u8>)> {
+        let idl = Idl::from_file(Path::new(idl::DEFAULT_IDL_PATH)).unwrap().unwrap();
+        let mint = Pubkey::new_unique();
+        let fields = (
+            OFTType::Native,
+            1000u64,
+            mint.to_bytes(),
+            Pubkey::new_unique().to_bytes(),
+            Pubkey::from_str(ENDPOINT).unwrap().to_bytes(),
+            255u8,
+            0u64,
+            admin.to_bytes(),
+            0u16,
+            false,
+            None::<[u8; 32]>,
+            None::<[u8; 32]>,
+        );
+        let mut store = idl.account_discriminator("OFTStore").unwrap().to_vec();
+        store.extend(borsh::to_vec(&fields).unwrap());
+        let mut mint_data = vec![0; 82];
+        mint_data[44] = 9;
+        mint_data[45] = 1;
+        HashMap::from([
+            (oft_store.to_string(), (Pubkey::from_str(DEFAULT_OFT_PROGRAM_ID).unwrap(), store)),
+            (mint.to_string(), (TOKEN_PROGRAM_ID, mint_data)),
+        ])
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn exports_peer_options_and_limits_before_the_peer_config_exists() {
+        let (oft_store, admin) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let manifest = manifest(&serve(accounts(&oft_store, &admin)));
+        let mut state = DeploymentState::default();
+        state.chains.insert("solana".to_string(), ChainDeployment::Solana {
+            mint: Pubkey::new_unique().to_string(),
+            oft_pda: oft_store.to_string(),
+            signature: String::new(),
+            spec: Some(manifest.token.solana_oft().spec()),
+        });
+        state.chains.insert("evm".to_string(), ChainDeployment::Evm {
+            chain_id: 1,
+            address: "0x00000000000000000000000000000000000000aa".to_string(),
+            tx_hash: None,
+            spec: Some(manifest.token.oft_params(&Default::default()).spec()),
+        });
+
+        let dir = std::env::temp_dir().join(format!("oft-apply-export-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let mut exporter = Some(Exporter::open(&dir).unwrap());
+        run(&manifest, &mut state, &dir.join("state.json"), &mut exporter).await.unwrap();
+        let exported = Exported::load(&dir).unwrap();
+        let fields: Vec<Vec<String>> = exported
+            .actions
+            .iter()
+            .map(|action| {
+                assert_eq!(action.chain, "solana");
+                action.checks.iter().map(|check| serde_json::to_value(check).unwrap()["field"].as_str().unwrap().to_string()).collect()
+            })
+            .collect();
+        assert_eq!(fields, vec![
+            vec!["peer_address"],
+            vec!["enforced_options"],
+            vec!["outbound_rate_limit", "inbound_rate_limit"],
+        ]);
+        let Check::SolanaPeer { expected, .. } = &exported.actions[2].checks[0] else { panic!("peer check") };
+        assert_eq!(expected, "capacity 1000, +10/s");
+        assert!(state.peers.is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,249 @@
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::str::FromStr;
+use anyhow::Result;
+use crate::evm_ops::OftParams;
+use crate::export::Submit;
+use crate::manifest::PeerSpec;
+use crate::network::{EvmProfile, NetworkProfile};
+use crate::signer::Signers;
+use crate::solana_ops::SolanaOftSpec;
+use crate::state::{ChainDeployment, DeploymentState, PeerRecord};
+use crate::{evm_ops, preflight, solana_ops, utils};
+
+pub const SOLANA_CHAIN: &str = "solana";
+
+/// Address of the OFT deployed on a chain, in its native form.
+pub enum Deployed {
+    Evm(String),
+    Solana(Pubkey),
+}
+
+impl Deployed {
+    pub fn to_bytes32(&self) -> Result<[u8; 32]> {
+        match self {
+            Deployed::Evm(addr) => utils::pad_evm_address(addr),
+            Deployed::Solana(pda) => Ok(pda.to_bytes()),
+        }
+    }
+}
+
+/// One EVM chain of the deployment and the contract it gets.
+pub struct EvmTarget {
+    pub profile: EvmProfile,
+    pub oft: OftParams,
+}
+
+/// Inputs of the deploy flow, resolved from the CLI and network profile.
+pub struct DeployConfig {
+    pub network: String,
+    pub profile: NetworkProfile,
+    pub evm: Vec<EvmTarget>,
+    pub solana_oft: SolanaOftSpec,
+    /// Directed peer links to wire, between `SOLANA_CHAIN` and EVM profile names.
+    pub links: Vec<PeerSpec>,
+    pub signers: Signers,
+}
+
+impl DeployConfig {
+    /// Every chain name of the deployment, Solana first.
+    pub fn chain_names(&self) -> Vec<String> {
+        std::iter::once(SOLANA_CHAIN.to_string())
+            .chain(self.evm.iter().map(|t| t.profile.name.clone()))
+            .collect()
+    }
+
+    /// EID of a chain in the deployment.
+    pub fn eid(&self, chain: &str) -> u32 {
+        if chain == SOLANA_CHAIN {
+            return self.profile.solana.eid;
+        }
+        self.evm_target(chain).profile.eid
+    }
+
+    pub fn evm_target(&self, chain: &str) -> &EvmTarget {
+        self.evm.iter().find(|t| t.profile.name == chain).expect("validated chain")
+    }
+}
+
+/// Every ordered pair of distinct chains.
+pub fn full_mesh(chains: &[String]) -> Vec<PeerSpec> {
+    let mut links = Vec::new();
+    for from in chains {
+        for to in chains.iter().filter(|to| *to != from) {
+            links.push(PeerSpec { from: from.clone(), to: to.clone(), enforced_options: None, rate_limit: None });
+        }
+    }
+    links
+}
+
+/// Parses `--link FROM:TO` values, checking both ends are part of the deployment.
+pub fn parse_links(values: &[String], chains: &[String]) -> Result<Vec<PeerSpec>> {
+    values
+        .iter()
+        .map(|value| {
+            let (from, to) = value
+                .split_once(':')
+                .ok_or_else(|| anyhow::anyhow!("Invalid link '{}', expected FROM:TO", value))?;
+            for name in [from, to] {
+                if !chains.iter().any(|c| c == name) {
+                    anyhow::bail!("Link {} references unknown chain '{}'. Chains: {}", value, name, chains.join(", "));
+                }
+            }
+            if from == to {
+                anyhow::bail!("Chain '{}' cannot be its own peer", from);
+            }
+            Ok(PeerSpec { from: from.to_string(), to: to.to_string(), enforced_options: None, rate_limit: None })
+        })
+        .collect()
+}
+
+/// Decimals preflight over every chain, using what is already deployed where `state` has it.
+pub async fn check_decimals(config: &DeployConfig, state: &DeploymentState) -> Result<()> {
+    let solana_pda = config.solana_oft.recorded_pda(state, SOLANA_CHAIN)?.map(|pda| Pubkey::from_str(&pda)).transpose()?;
+    let mut chains = vec![(
+        SOLANA_CHAIN.to_string(),
+        solana_ops::oft_decimals(&config.profile.solana, &config.solana_oft, solana_pda.as_ref())?,
+    )];
+    for target in &config.evm {
+        let evm_addr = state.evm_oft(&target.profile.name, &target.oft.spec())?;
+        let decimals = evm_ops::oft_decimals(&target.profile, &target.oft.flavour, evm_addr.as_deref()).await?;
+        chains.push((target.profile.name.clone(), decimals));
+    }
+    preflight::check_decimals(&chains)
+}
+
+/// Deploys Solana and every EVM chain, wires each link with the remote chain's EID and hands
+/// the EVM OFTs to their delegate, skipping steps already recorded in `state`.
+pub async fn run(config: &DeployConfig, state: &mut DeploymentState, state_path: &std::path::Path) -> Result<()> {
+    let DeployConfig { profile, solana_oft, signers, .. } = config;
+    check_decimals(config, state).await?;
+    let mut deployed: HashMap<String, Deployed> = HashMap::new();
+
+    // 1. Setup Solana Side
+    let sol_oft_pda = match solana_oft.recorded_pda(state, SOLANA_CHAIN)? {
+        Some(pda) => {
+            println!("⏭️  Solana OFT already deployed: {}", pda);
+            Pubkey::from_str(&pda)?
+        }
+        None => {
+            let (mint, pda, sig) = solana_ops::init_oft(&profile.solana, &signers.solana(SOLANA_CHAIN), solana_oft).await?;
+            state.chains.insert(SOLANA_CHAIN.to_string(), ChainDeployment::Solana {
+                mint: mint.to_string(),
+                oft_pda: pda.to_string(),
+                signature: sig.to_string(),
+                spec: Some(solana_oft.spec()),
+            });
+            state.save(state_path)?;
+            pda
+        }
+    };
+    deployed.insert(SOLANA_CHAIN.to_string(), Deployed::Solana(sol_oft_pda));
+
+    // 2. Setup EVM Side
+    for target in &config.evm {
+        let evm_profile = &target.profile;
+        let evm_oft_addr = match state.evm_oft(&evm_profile.name, &target.oft.spec())? {
+            Some(addr) => {
+                println!("⏭️  {} OFT already deployed: {}", evm_profile.name, addr);
+                addr
+            }
+            None => {
+                let (addr, tx_hash) = evm_ops::deploy_evm_oft(evm_profile, &signers.evm(&evm_profile.name), &target.oft).await?;
+                println!("📝 Captured EVM Address: {}", addr);
+                state.chains.insert(evm_profile.name.clone(), ChainDeployment::Evm {
+                    chain_id: evm_profile.chain_id,
+                    address: addr.clone(),
+                    tx_hash: Some(tx_hash),
+                    spec: Some(target.oft.spec()),
+                });
+                state.save(state_path)?;
+                addr
+            }
+        };
+        deployed.insert(evm_profile.name.clone(), Deployed::Evm(evm_oft_addr));
+    }
+
+    // 3. Wire every link: `from` registers `to` under `to`'s EID
+    for link in &config.links {
+        let remote_eid = config.eid(&link.to);
+        let remote_bytes = deployed[&link.to].to_bytes32()?;
+        let peer_hex = format!("0x{}", hex::encode(remote_bytes));
+        if state.has_peer(&link.from, remote_eid, &peer_hex) {
+            println!("⏭️  {} -> {} peer already set", link.from, link.to);
+            continue;
+        }
+
+        let tx = match &deployed[&link.from] {
+            Deployed::Solana(pda) => {
+                let mut submit = Submit::Sign(signers.solana(SOLANA_CHAIN));
+                let sig = solana_ops::set_peer_solana(&profile.solana, &mut submit, SOLANA_CHAIN, *pda, remote_eid, remote_bytes).await?;
+                sig.map(|sig| sig.to_string())
+            }
+            Deployed::Evm(addr) => {
+                let evm_profile = &config.evm_target(&link.from).profile;
+                evm_ops::set_peer_evm(evm_profile, &mut Submit::Sign(signers.evm(&link.from)), addr, remote_eid, remote_bytes).await?
+            }
+        };
+        state.record_peer(PeerRecord {
+            from: link.from.clone(),
+            to: link.to.clone(),
+            remote_eid,
+            peer: peer_hex,
+            tx,
+        });
+        state.save(state_path)?;
+    }
+
+    // 4. Hand ownership to the requested delegate now that the deployer is done wiring
+    for target in &config.evm {
+        if let Deployed::Evm(addr) = &deployed[&target.profile.name] {
+            let mut submit = Submit::Sign(signers.evm(&target.profile.name));
+            evm_ops::hand_over_evm(&target.profile, &mut submit, addr, &target.oft).await?;
+        }
+    }
+
+    println!("🚀 Cross-chain setup complete! State saved to {}", state_path.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chains() -> Vec<String> {
+        ["solana", "sepolia", "arbitrum-sepolia"].iter().map(|c| c.to_string()).collect()
+    }
+
+    fn pairs(links: &[PeerSpec]) -> Vec<(&str, &str)> {
+        links.iter().map(|l| (l.from.as_str(), l.to.as_str())).collect()
+    }
+
+    #[test]
+    fn full_mesh_links_every_ordered_pair_once() {
+        let chains = chains();
+        let links = full_mesh(&chains);
+        assert_eq!(links.len(), chains.len() * (chains.len() - 1));
+        for from in &chains {
+            for to in chains.iter().filter(|to| *to != from) {
+                assert_eq!(pairs(&links).iter().filter(|p| **p == (from.as_str(), to.as_str())).count(), 1);
+            }
+        }
+        assert!(links.iter().all(|l| l.from != l.to));
+    }
+
+    #[test]
+    fn parses_links() {
+        let values = vec!["solana:sepolia".to_string(), "sepolia:solana".to_string()];
+        let links = parse_links(&values, &chains()).unwrap();
+        assert_eq!(pairs(&links), vec![("solana", "sepolia"), ("sepolia", "solana")]);
+    }
+
+    #[test]
+    fn rejects_malformed_links() {
+        let err = |value: &str| parse_links(&[value.to_string()], &chains()).unwrap_err().to_string();
+        assert!(err("solana-sepolia").contains("expected FROM:TO"));
+        assert!(err("solana:base").contains("unknown chain 'base'"));
+        assert!(err("sepolia:sepolia").contains("cannot be its own peer"));
+    }
+}
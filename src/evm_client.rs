@@ -0,0 +1,476 @@
+use ethabi::{ethereum_types::{Address, U256}, ParamType, Token};
+use rlp::RlpStream;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use solana_sdk::keccak;
+use std::time::Duration;
+use anyhow::{Result, Context};
+use crate::signer::EvmSigner;
+
+const RECEIPT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+const RECEIPT_TIMEOUT: Duration = Duration::from_secs(180);
+const DEFAULT_PRIORITY_FEE: u64 = 1_000_000_000;
+
+/// Errors an OFT/OApp call commonly reverts with, used to name custom error selectors.
+const KNOWN_ERRORS: &[&str] = &[
+    "OwnableUnauthorizedAccount(address)",
+    "OwnableInvalidOwner(address)",
+    "NoPeer(uint32)",
+    "OnlyPeer(uint32,bytes32)",
+    "OnlyEndpoint(address)",
+    "InvalidDelegate()",
+    "InvalidEndpointCall()",
+    "InvalidOptions(bytes)",
+    "InvalidLocalDecimals()",
+    "SlippageExceeded(uint256,uint256)",
+    "NotEnoughNative(uint256)",
+    "LzTokenUnavailable()",
+];
+
+/// Minimal in-process EVM JSON-RPC client that signs EIP-1559 transactions locally, or
+/// EIP-155 legacy ones on chains without a base fee.
+pub struct EvmClient {
+    http: reqwest::Client,
+    rpc_url: String,
+    chain_id: u64,
+    signer: Option<EvmSigner>,
+}
+
+/// Mined transaction, as reported by `eth_getTransactionReceipt`.
+pub struct TxReceipt {
+    pub tx_hash: String,
+    pub contract_address: Option<Address>,
+    pub gas_used: U256,
+    pub logs: Vec<TxLog>,
+}
+
+/// Event log of a mined transaction.
+pub struct TxLog {
+    pub address: Address,
+    pub topics: Vec<Vec<u8>>,
+}
+
+/// Error object of a JSON-RPC response, as opposed to a failed request.
+#[derive(Debug)]
+pub struct RpcError {
+    method: String,
+    code: i64,
+    message: String,
+    /// Revert data, if the node returned any.
+    data: Option<Vec<u8>>,
+}
+
+impl RpcError {
+    /// The call ran and reverted: code 3 with revert data on geth, -32000 "execution reverted" elsewhere.
+    pub fn is_revert(&self) -> bool {
+        self.code == 3 || self.message.contains("revert")
+    }
+}
+
+impl std::fmt::Display for RpcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.data {
+            Some(data) => write!(f, "{}: {} ({})", self.method, self.message, decode_revert(data)),
+            None => write!(f, "{}: {}", self.method, self.message),
+        }
+    }
+}
+
+impl std::error::Error for RpcError {}
+
+impl EvmClient {
+    /// Read-only client; `send_transaction` will fail.
+    pub fn new(rpc_url: &str, chain_id: u32) -> Self {
+        EvmClient {
+            http: reqwest::Client::new(),
+            rpc_url: rpc_url.to_string(),
+            chain_id: chain_id as u64,
+            signer: None,
+        }
+    }
+
+    pub fn with_signer(rpc_url: &str, chain_id: u32, signer: EvmSigner) -> Self {
+        EvmClient { signer: Some(signer), ..Self::new(rpc_url, chain_id) }
+    }
+
+    /// Address of the configured signer.
+    pub fn address(&self) -> Result<Address> {
+        Ok(self.signer.as_ref().context("EVM client has no signer configured")?.address())
+    }
+
+    async fn rpc<T: DeserializeOwned>(&self, method: &str, params: Value) -> Result<T> {
+        let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let response: Value = self
+            .http
+            .post(&self.rpc_url)
+            .json(&request)
+            .send()
+            .await
+            .with_context(|| format!("{} request to {} failed", method, self.rpc_url))?
+            .json()
+            .await
+            .with_context(|| format!("{} returned a non-JSON response", method))?;
+
+        if let Some(error) = response.get("error") {
+            return Err(RpcError {
+                method: method.to_string(),
+                code: error["code"].as_i64().unwrap_or_default(),
+                message: error["message"].as_str().unwrap_or("unknown error").to_string(),
+                data: error
+                    .get("data")
+                    .and_then(|d| d.as_str())
+                    .and_then(|d| hex::decode(d.trim_start_matches("0x")).ok())
+                    .filter(|d| !d.is_empty()),
+            }
+            .into());
+        }
+        serde_json::from_value(response["result"].clone())
+            .with_context(|| format!("Unexpected {} result: {}", method, response["result"]))
+    }
+
+    pub async fn call(&self, to: Address, data: &[u8]) -> Result<Vec<u8>> {
+        let result: String = self
+            .rpc("eth_call", json!([{ "to": to, "data": hex_data(data) }, "latest"]))
+            .await?;
+        Ok(hex::decode(result.trim_start_matches("0x"))?)
+    }
+
+    /// `call`, or `None` if the call reverts.
+    pub async fn try_call(&self, to: Address, data: &[u8]) -> Result<Option<Vec<u8>>> {
+        match self.call(to, data).await {
+            Ok(output) => Ok(Some(output)),
+            Err(e) if e.downcast_ref::<RpcError>().is_some_and(RpcError::is_revert) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub async fn code(&self, address: Address) -> Result<Vec<u8>> {
+        let result: String = self.rpc("eth_getCode", json!([address, "latest"])).await?;
+        Ok(hex::decode(result.trim_start_matches("0x"))?)
+    }
+
+    /// Next nonce of the configured signer, counting pending transactions.
+    pub async fn nonce(&self) -> Result<U256> {
+        parse_quantity(&self.rpc::<String>("eth_getTransactionCount", json!([self.address()?, "pending"])).await?)
+    }
+
+    /// Address a contract deployed by the signer at `nonce` will get.
+    pub fn create_address(&self, nonce: U256) -> Result<Address> {
+        Ok(contract_address(self.address()?, nonce))
+    }
+
+    /// `eth_estimateGas` from the signer; fails with the decoded revert reason if the call would revert.
+    pub async fn estimate_gas(&self, to: Option<Address>, data: &[u8], value: U256) -> Result<U256> {
+        let estimated: String = self.rpc("eth_estimateGas", json!([self.call_object(to, data, value)?])).await?;
+        parse_quantity(&estimated)
+    }
+
+    fn call_object(&self, to: Option<Address>, data: &[u8], value: U256) -> Result<Value> {
+        let mut call = json!({ "from": self.address()?, "data": hex_data(data), "value": format!("{:#x}", value) });
+        if let Some(to) = to {
+            call["to"] = json!(to);
+        }
+        Ok(call)
+    }
+
+    /// Estimates gas, signs and broadcasts a transaction, then waits for its receipt.
+    /// `to = None` deploys `data` as init code. Reverts are reported with their decoded reason.
+    pub async fn send_transaction(&self, to: Option<Address>, data: Vec<u8>, value: U256) -> Result<TxReceipt> {
+        let signer = self.signer.as_ref().context("EVM client has no signer configured")?;
+        let call = self.call_object(to, &data, value)?;
+
+        let gas_limit = self.estimate_gas(to, &data, value).await? * 12 / 10;
+        let nonce = self.nonce().await?;
+        let block: Value = self.rpc("eth_getBlockByNumber", json!(["latest", false])).await?;
+        // Chains without EIP-1559 report no base fee and drop type-2 transactions
+        let fee = match block["baseFeePerGas"].as_str() {
+            Some(base_fee) => {
+                let priority_fee = match self.rpc::<String>("eth_maxPriorityFeePerGas", json!([])).await {
+                    Ok(fee) => parse_quantity(&fee)?,
+                    Err(_) => U256::from(DEFAULT_PRIORITY_FEE),
+                };
+                GasFee::Eip1559 { priority_fee, max_fee: parse_quantity(base_fee)? * 2 + priority_fee }
+            }
+            None => GasFee::Legacy(parse_quantity(&self.rpc::<String>("eth_gasPrice", json!([])).await?)?),
+        };
+        let tx = UnsignedTx { chain_id: self.chain_id, nonce, fee, gas_limit, to, value, data };
+
+        let (signature, recovery_id) = signer.sign_digest(&tx.sighash()).await?;
+        let raw = tx.encode_signed(&signature, recovery_id);
+
+        let tx_hash: String = self.rpc("eth_sendRawTransaction", json!([hex_data(&raw)])).await?;
+        println!("📨 Broadcast tx {}", tx_hash);
+
+        let receipt = self.wait_for_receipt(&tx_hash).await?;
+        if receipt["status"].as_str() != Some("0x1") {
+            // Replay against the state the transaction ran on, i.e. before its block
+            let block = parse_quantity(receipt["blockNumber"].as_str().context("Receipt has no blockNumber")?)?;
+            let parent = format!("{:#x}", block.saturating_sub(U256::one()));
+            let reason = match self.rpc::<String>("eth_call", json!([call, parent])).await {
+                Err(e) => e.to_string(),
+                Ok(_) => "no revert data".to_string(),
+            };
+            anyhow::bail!("Transaction {} reverted: {}", tx_hash, reason);
+        }
+
+        Ok(TxReceipt {
+            tx_hash,
+            contract_address: receipt["contractAddress"].as_str().map(parse_address).transpose()?,
+            gas_used: receipt["gasUsed"].as_str().map(parse_quantity).transpose()?.unwrap_or_default(),
+            logs: receipt["logs"].as_array().map(|logs| logs.iter().map(parse_log).collect()).transpose()?.unwrap_or_default(),
+        })
+    }
+
+    async fn wait_for_receipt(&self, tx_hash: &str) -> Result<Value> {
+        let started = std::time::Instant::now();
+        loop {
+            let receipt: Value = self.rpc("eth_getTransactionReceipt", json!([tx_hash])).await?;
+            if !receipt.is_null() {
+                return Ok(receipt);
+            }
+            if started.elapsed() > RECEIPT_TIMEOUT {
+                anyhow::bail!("Timed out waiting for receipt of {}", tx_hash);
+            }
+            tokio::time::sleep(RECEIPT_POLL_INTERVAL).await;
+        }
+    }
+}
+
+/// Gas pricing of a transaction: type-2 where the chain has a base fee, legacy otherwise.
+enum GasFee {
+    Eip1559 { priority_fee: U256, max_fee: U256 },
+    Legacy(U256),
+}
+
+/// Transaction fields before signing.
+struct UnsignedTx {
+    chain_id: u64,
+    nonce: U256,
+    fee: GasFee,
+    gas_limit: U256,
+    to: Option<Address>,
+    value: U256,
+    data: Vec<u8>,
+}
+
+impl UnsignedTx {
+    /// Fields shared by the signing payload and the signed transaction, in RLP order.
+    fn append_fields(&self, s: &mut RlpStream) {
+        match self.fee {
+            GasFee::Eip1559 { priority_fee, max_fee } => {
+                append_uint(s, U256::from(self.chain_id));
+                append_uint(s, self.nonce);
+                append_uint(s, priority_fee);
+                append_uint(s, max_fee);
+            }
+            GasFee::Legacy(gas_price) => {
+                append_uint(s, self.nonce);
+                append_uint(s, gas_price);
+            }
+        }
+        append_uint(s, self.gas_limit);
+        match self.to {
+            Some(to) => s.append(&to.as_bytes()),
+            None => s.append_empty_data(),
+        };
+        append_uint(s, self.value);
+        s.append(&self.data);
+        if let GasFee::Eip1559 { .. } = self.fee {
+            s.begin_list(0);
+        }
+    }
+
+    /// EIP-1559 `keccak(0x02 || rlp(fields))`, or EIP-155 `keccak(rlp(fields, chainId, 0, 0))`.
+    fn sighash(&self) -> [u8; 32] {
+        match self.fee {
+            GasFee::Eip1559 { .. } => {
+                let mut unsigned = RlpStream::new_list(9);
+                self.append_fields(&mut unsigned);
+                keccak::hash(&[&[0x02u8][..], &unsigned.out()[..]].concat()).to_bytes()
+            }
+            GasFee::Legacy(_) => {
+                let mut unsigned = RlpStream::new_list(9);
+                self.append_fields(&mut unsigned);
+                append_uint(&mut unsigned, U256::from(self.chain_id));
+                unsigned.append_empty_data();
+                unsigned.append_empty_data();
+                keccak::hash(&unsigned.out()).to_bytes()
+            }
+        }
+    }
+
+    /// Raw transaction for `eth_sendRawTransaction`; legacy `v` is `recovery_id + 2 * chainId + 35`.
+    fn encode_signed(&self, signature: &[u8; 64], recovery_id: u8) -> Vec<u8> {
+        let r = U256::from_big_endian(&signature[..32]);
+        let s = U256::from_big_endian(&signature[32..]);
+        match self.fee {
+            GasFee::Eip1559 { .. } => {
+                let mut signed = RlpStream::new_list(12);
+                self.append_fields(&mut signed);
+                signed.append(&recovery_id);
+                append_uint(&mut signed, r);
+                append_uint(&mut signed, s);
+                [&[0x02u8][..], &signed.out()[..]].concat()
+            }
+            GasFee::Legacy(_) => {
+                let mut signed = RlpStream::new_list(9);
+                self.append_fields(&mut signed);
+                append_uint(&mut signed, U256::from(recovery_id) + U256::from(self.chain_id) * 2 + 35);
+                append_uint(&mut signed, r);
+                append_uint(&mut signed, s);
+                signed.out().to_vec()
+            }
+        }
+    }
+}
+
+/// CREATE address of a contract deployed by `sender` at `nonce`: keccak(rlp([sender, nonce]))[12..].
+pub fn contract_address(sender: Address, nonce: U256) -> Address {
+    let mut stream = RlpStream::new_list(2);
+    stream.append(&sender.as_bytes());
+    append_uint(&mut stream, nonce);
+    let hash = keccak::hash(&stream.out());
+    Address::from_slice(&hash.as_ref()[12..])
+}
+
+/// Turns revert data into a readable reason: `Error(string)`, `Panic(uint256)` or a known custom error.
+pub fn decode_revert(data: &[u8]) -> String {
+    if data.len() < 4 {
+        return format!("revert data 0x{}", hex::encode(data));
+    }
+    let (selector, args) = data.split_at(4);
+    if selector == ethabi::short_signature("Error", &[ParamType::String]) {
+        if let Ok(tokens) = ethabi::decode(&[ParamType::String], args) {
+            if let Some(Token::String(reason)) = tokens.into_iter().next() {
+                return reason;
+            }
+        }
+    }
+    if selector == ethabi::short_signature("Panic", &[ParamType::Uint(256)]) {
+        return format!("panic 0x{}", hex::encode(args));
+    }
+    for signature in KNOWN_ERRORS {
+        if selector == &keccak::hash(signature.as_bytes()).as_ref()[..4] {
+            return format!("{} args 0x{}", signature, hex::encode(args));
+        }
+    }
+    format!("custom error 0x{} args 0x{}", hex::encode(selector), hex::encode(args))
+}
+
+pub fn parse_address(addr: &str) -> Result<Address> {
+    addr.trim().parse().with_context(|| format!("Invalid EVM address: {}", addr))
+}
+
+fn parse_log(log: &Value) -> Result<TxLog> {
+    let topic = |value: &Value| -> Result<Vec<u8>> {
+        let value = value.as_str().with_context(|| format!("Unexpected log topic: {}", value))?;
+        Ok(hex::decode(value.trim_start_matches("0x"))?)
+    };
+    Ok(TxLog {
+        address: parse_address(log["address"].as_str().context("Log has no address")?)?,
+        topics: log["topics"].as_array().context("Log has no topics")?.iter().map(topic).collect::<Result<_>>()?,
+    })
+}
+
+fn parse_quantity(value: &str) -> Result<U256> {
+    U256::from_str_radix(value.trim_start_matches("0x"), 16)
+        .with_context(|| format!("Invalid hex quantity: {}", value))
+}
+
+fn hex_data(data: &[u8]) -> String {
+    format!("0x{}", hex::encode(data))
+}
+
+/// RLP integers are big-endian with no leading zeros.
+fn append_uint(stream: &mut RlpStream, value: U256) {
+    let mut buf = [0u8; 32];
+    value.to_big_endian(&mut buf);
+    let start = buf.iter().position(|b| *b != 0).unwrap_or(32);
+    stream.append(&&buf[start..]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Signs `tx` with the EIP-155 example key (0x4646...46).
+    fn sign(tx: &UnsignedTx) -> Vec<u8> {
+        let key = libsecp256k1::SecretKey::parse(&[0x46; 32]).unwrap();
+        let (signature, recovery_id) = libsecp256k1::sign(&libsecp256k1::Message::parse(&tx.sighash()), &key);
+        tx.encode_signed(&signature.serialize(), recovery_id.serialize())
+    }
+
+    fn transfer(fee: GasFee, data: Vec<u8>) -> UnsignedTx {
+        UnsignedTx {
+            chain_id: 1,
+            nonce: 9.into(),
+            fee,
+            gas_limit: 21_000.into(),
+            to: Some(Address::repeat_byte(0x35)),
+            value: U256::exp10(18),
+            data,
+        }
+    }
+
+    #[test]
+    fn signs_eip155_example() {
+        let tx = transfer(GasFee::Legacy(20_000_000_000u64.into()), Vec::new());
+        assert_eq!(
+            hex::encode(tx.sighash()),
+            "daf5a779ae972f972197303d7b574746c7ef83eadac0f2791ad23db92e4c8e53"
+        );
+        assert_eq!(
+            hex::encode(sign(&tx)),
+            "f86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83"
+        );
+    }
+
+    #[test]
+    fn signs_eip1559_transaction() {
+        let fee = GasFee::Eip1559 { priority_fee: 2_000_000_000u64.into(), max_fee: 100_000_000_000u64.into() };
+        let tx = transfer(fee, vec![0xde, 0xad, 0xbe, 0xef]);
+        assert_eq!(
+            hex::encode(sign(&tx)),
+            "02f8770109847735940085174876e800825208943535353535353535353535353535353535353535880de0b6b3a764000084deadbeefc001a05055e08f9eb4533cbaed25ae861fcb4196c20a430cbbafb451f0f9344dd85257a06f7da5240e4893df8efa3fb9a4871f7ba72845f631dd8cfd49aa9fc2ea33b017"
+        );
+    }
+
+    #[test]
+    fn derives_create_addresses() {
+        let sender = parse_address("0x6ac7ea33f8831ea9dcc53393aaa88b25a785dbf0").unwrap();
+        let expected = [
+            "0xcd234a471b72ba2f1ccf0a70fcaba648a5eecd8d",
+            "0x343c43a37d37dff08ae8c4a11544c718abb4fcf8",
+            "0xf778b86fa74e846c4f0a1fbd1335fe81c00a0c91",
+        ];
+        for (nonce, address) in expected.iter().enumerate() {
+            assert_eq!(contract_address(sender, nonce.into()), parse_address(address).unwrap());
+        }
+    }
+
+    #[test]
+    fn appends_minimal_uints() {
+        let encode = |value: u64| {
+            let mut stream = RlpStream::new();
+            append_uint(&mut stream, value.into());
+            hex::encode(stream.out())
+        };
+        assert_eq!(encode(0), "80");
+        assert_eq!(encode(0x7f), "7f");
+        assert_eq!(encode(0x80), "8180");
+        assert_eq!(encode(0x0400), "820400");
+    }
+
+    #[test]
+    fn tells_reverts_from_rpc_failures() {
+        let error = |code: i64, message: &str| RpcError { method: "eth_call".to_string(), code, message: message.to_string(), data: None };
+        assert!(error(3, "execution reverted").is_revert());
+        assert!(error(-32000, "execution reverted").is_revert());
+        assert!(!error(-32000, "header not found").is_revert());
+        assert!(!error(-32005, "rate limit exceeded").is_revert());
+        let reason = ethabi::encode(&[Token::String("Pausable: paused".to_string())]);
+        let data = [&ethabi::short_signature("Error", &[ParamType::String])[..], &reason].concat();
+        let reverted = RpcError { data: Some(data), ..error(3, "execution reverted") };
+        assert_eq!(reverted.to_string(), "eth_call: execution reverted (Pausable: paused)");
+    }
+}
@@ -1,57 +1,717 @@
-use std::process::Command;
+use ethabi::{ethereum_types::{Address, U256}, Contract, ParamType, Token};
+use clap::ValueEnum;
+use serde::Deserialize;
 use anyhow::{Result, Context};
-use crate::utils;
-
-pub async fn deploy_evm_oft(chain_id: u32, endpoint_address: &str) -> Result<String> {
-    println!("🛠️  Spawning Foundry to deploy OFT on chain ID {}...", chain_id);
-
-    let rpc_url = "https://ethereum-sepolia-rpc.publicnode.com"; 
-
-    let output = Command::new("forge")
-        .current_dir("./evm")
-        .arg("script")
-        .arg("script/DeployOFT.s.sol:DeployOFT")
-        .arg("--sig")
-        .arg("run(address)") 
-        .arg(endpoint_address)
-        .arg("--rpc-url")
-        .arg(rpc_url)
-        .arg("--broadcast")
-        .output()
-        .context("Failed to execute forge script")?;
-
-    let stdout = String::from_utf8(output.stdout)?;
-    let stderr = String::from_utf8(output.stderr)?;
-
-    println!("--- FORGE STDOUT ---\n{}", stdout);
-    if !stderr.is_empty() {
-        println!("--- FORGE STDERR ---\n{}", stderr);
-    }
-    
-    if !output.status.success() {
-        anyhow::bail!("Foundry script failed: {}", stderr);
-    }
-
-    let address = utils::parse_forge_output(&stdout)
-        .context("Could not find DEPLOYED_ADDR in forge output. Check STDOUT above.")?;
-
-    Ok(address)
-}
-
-pub async fn set_peer_evm(oft_addr: &str, target_eid: u32, peer_bytes: String) -> Result<()> {
-    println!("🔗 Wiring EVM -> Solana...");
-    println!("(Simulated) Executing: cast send {} setPeer({}, {})", oft_addr, target_eid, peer_bytes);
-    
-   
-    Command::new("cast")
-       .arg("send")
-       .arg(oft_addr)
-       .arg("setPeer(uint32,bytes32)")
-       .arg(target_eid.to_string())
-       .arg(peer_bytes)
-       .arg("--rpc-url").arg("https://ethereum-sepolia-rpc.publicnode.com")
-       .arg("--private-key").arg(std::env::var("PRIVATE_KEY")?)
-       .output()?;
-    
+use crate::evm_client::{self, EvmClient};
+use crate::export::{Check, Submit};
+use crate::network::EvmProfile;
+use crate::oft_client;
+use crate::options::{self, EnforcedOptionsSpec};
+use crate::preflight::{self, Decimals};
+use crate::signer::KeySource;
+
+/// `OFTCore.sharedDecimals()` unless overridden.
+pub const DEFAULT_SHARED_DECIMALS: u8 = 6;
+
+/// ERC20 `decimals()` of a plain OFT.
+const OFT_LOCAL_DECIMALS: u8 = 18;
+
+/// Contract deployed on an EVM chain, each a thin `evm/src/My*.sol` wrapper over `oft-evm`.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum OftFlavour {
+    /// New ERC20 that mints and burns itself.
+    #[default]
+    Oft,
+    /// Locks and unlocks an existing ERC20.
+    Adapter { token: String },
+    /// Burns and mints an existing ERC20 through its `IMintableBurnable` minter.
+    MintBurnAdapter { token: String, minter_burner: String },
+    /// Locks and unlocks the native gas token.
+    NativeAdapter {
+        #[serde(default = "default_native_decimals")]
+        local_decimals: u8,
+    },
+}
+
+pub fn default_native_decimals() -> u8 {
+    18
+}
+
+impl OftFlavour {
+    pub fn contract_name(&self) -> &'static str {
+        match self {
+            OftFlavour::Oft => "MyOFT",
+            OftFlavour::Adapter { .. } => "MyOFTAdapter",
+            OftFlavour::MintBurnAdapter { .. } => "MyMintBurnOFTAdapter",
+            OftFlavour::NativeAdapter { .. } => "MyNativeOFTAdapter",
+        }
+    }
+
+    fn artifact_path(&self) -> String {
+        format!("./evm/out/{0}.sol/{0}.json", self.contract_name())
+    }
+
+    /// Token moved by the contract: itself for an OFT, the zero address for native.
+    fn inner_token(&self) -> Result<Option<Address>> {
+        match self {
+            OftFlavour::Oft => Ok(None),
+            OftFlavour::Adapter { token } | OftFlavour::MintBurnAdapter { token, .. } => Ok(Some(evm_client::parse_address(token)?)),
+            OftFlavour::NativeAdapter { .. } => Ok(Some(Address::zero())),
+        }
+    }
+}
+
+/// `--evm-oft-type` values, one per [`OftFlavour`].
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum EvmOftType {
+    Oft,
+    Adapter,
+    MintBurnAdapter,
+    NativeAdapter,
+}
+
+impl EvmOftType {
+    /// Combines the type with its CLI arguments; the minter/burner defaults to the token itself.
+    pub fn flavour(self, token: Option<&String>, minter_burner: Option<&String>, native_decimals: u8) -> Result<OftFlavour> {
+        let name = self.to_possible_value().expect("no skipped variants");
+        let token = || token.cloned().with_context(|| format!("--evm-oft-type {} needs --evm-token", name.get_name()));
+        Ok(match self {
+            EvmOftType::Oft => OftFlavour::Oft,
+            EvmOftType::Adapter => OftFlavour::Adapter { token: token()? },
+            EvmOftType::MintBurnAdapter => {
+                let token = token()?;
+                let minter_burner = minter_burner.cloned().unwrap_or_else(|| token.clone());
+                OftFlavour::MintBurnAdapter { token, minter_burner }
+            }
+            EvmOftType::NativeAdapter => OftFlavour::NativeAdapter { local_decimals: native_decimals },
+        })
+    }
+}
+
+/// Constructor metadata for the EVM OFT contract.
+#[derive(Clone, Debug)]
+pub struct OftParams {
+    /// ERC20 name and symbol; only used by a plain OFT.
+    pub name: String,
+    pub symbol: String,
+    /// Owner and endpoint delegate handed over once wiring is done; the deployer keeps both
+    /// when `None`.
+    pub delegate: Option<String>,
+    pub flavour: OftFlavour,
+}
+
+impl OftParams {
+    /// Recorded in the state so a rerun with other settings does not reuse this contract.
+    /// The delegate is left out: it only receives ownership after wiring.
+    pub fn spec(&self) -> String {
+        let contract = self.flavour.contract_name();
+        match &self.flavour {
+            OftFlavour::Oft => format!("{} name {:?} symbol {:?}", contract, self.name, self.symbol),
+            OftFlavour::Adapter { token } => format!("{} token {}", contract, token),
+            OftFlavour::MintBurnAdapter { token, minter_burner } => format!("{} token {} minter/burner {}", contract, token, minter_burner),
+            OftFlavour::NativeAdapter { local_decimals } => format!("{} local decimals {}", contract, local_decimals),
+        }
+    }
+}
+
+/// Compiled contract as written by `forge build`.
+struct Artifact {
+    abi: Contract,
+    bytecode: Vec<u8>,
+}
+
+fn load_artifact(path: &str) -> Result<Artifact> {
+    let raw = std::fs::read_to_string(path)
+        .with_context(|| format!("Missing artifact {}. Run `forge build` in ./evm first.", path))?;
+    let json: serde_json::Value = serde_json::from_str(&raw)?;
+    let abi = Contract::load(json["abi"].to_string().as_bytes()).context("Artifact has an invalid ABI")?;
+    let bytecode = json["bytecode"]["object"]
+        .as_str()
+        .context("Artifact has no bytecode.object")?;
+    Ok(Artifact { abi, bytecode: hex::decode(bytecode.trim_start_matches("0x"))? })
+}
+
+/// Init code for the flavour's constructor; every flavour takes `lzEndpoint, delegate` last.
+/// The deployer is made owner and delegate, since `setPeer` and `setEnforcedOptions` are
+/// `onlyOwner` and are signed with its key.
+fn deploy_data(artifact: &Artifact, client: &EvmClient, profile: &EvmProfile, params: &OftParams) -> Result<Vec<u8>> {
+    let constructor = artifact
+        .abi
+        .constructor()
+        .with_context(|| format!("{} ABI has no constructor", params.flavour.contract_name()))?;
+    let mut args = match &params.flavour {
+        OftFlavour::Oft => vec![Token::String(params.name.clone()), Token::String(params.symbol.clone())],
+        OftFlavour::Adapter { token } => vec![Token::Address(evm_client::parse_address(token)?)],
+        OftFlavour::MintBurnAdapter { token, minter_burner } => vec![
+            Token::Address(evm_client::parse_address(token)?),
+            Token::Address(evm_client::parse_address(minter_burner)?),
+        ],
+        OftFlavour::NativeAdapter { local_decimals } => vec![Token::Uint((*local_decimals).into())],
+    };
+    args.push(Token::Address(evm_client::parse_address(&profile.endpoint)?));
+    args.push(Token::Address(client.address()?));
+    Ok(constructor.encode_input(artifact.bytecode.clone(), &args)?)
+}
+
+/// `decimals()` of an ERC20.
+async fn erc20_decimals(client: &EvmClient, token: Address) -> Result<u8> {
+    Ok(view_uint(client, token, "decimals").await?.low_u32() as u8)
+}
+
+/// Checks that the contracts the constructor relies on exist: `OFTCore` reads the inner
+/// token's `decimals()` and would revert on an empty address.
+async fn check_flavour(client: &EvmClient, flavour: &OftFlavour) -> Result<()> {
+    if let OftFlavour::Adapter { token } | OftFlavour::MintBurnAdapter { token, .. } = flavour {
+        let token = evm_client::parse_address(token)?;
+        if client.code(token).await?.is_empty() {
+            anyhow::bail!("Inner token {:?} has no code on this chain", token);
+        }
+    }
+    if let OftFlavour::MintBurnAdapter { minter_burner, .. } = flavour {
+        let minter_burner = evm_client::parse_address(minter_burner)?;
+        if client.code(minter_burner).await?.is_empty() {
+            anyhow::bail!("Minter/burner {:?} has no code on this chain", minter_burner);
+        }
+    }
+    Ok(())
+}
+
+/// Decimals of the EVM OFT: `sharedDecimals()` and `decimalConversionRate()` if `deployed`
+/// has code, otherwise the local decimals the flavour will get and the default shared decimals.
+pub async fn oft_decimals(profile: &EvmProfile, flavour: &OftFlavour, deployed: Option<&str>) -> Result<Decimals> {
+    let client = EvmClient::new(&profile.rpc_url, profile.chain_id);
+    if let Some(address) = deployed {
+        let oft = evm_client::parse_address(address)?;
+        if !client.code(oft).await?.is_empty() {
+            let shared = view_uint(&client, oft, "sharedDecimals").await?.low_u32() as u8;
+            let rate = view_uint(&client, oft, "decimalConversionRate").await?.low_u64();
+            let rate_decimals = preflight::rate_decimals(rate).with_context(|| format!("OFT {} on {}", address, profile.name))?;
+            return Ok(Decimals { local: shared + rate_decimals, shared, deployed: true });
+        }
+    }
+    let local = match flavour {
+        OftFlavour::Oft => OFT_LOCAL_DECIMALS,
+        OftFlavour::Adapter { token } | OftFlavour::MintBurnAdapter { token, .. } => {
+            let token = evm_client::parse_address(token)?;
+            if client.code(token).await?.is_empty() {
+                anyhow::bail!("Inner token {:?} has no code on {}", token, profile.name);
+            }
+            erc20_decimals(&client, token).await?
+        }
+        OftFlavour::NativeAdapter { local_decimals } => *local_decimals,
+    };
+    Ok(Decimals { local, shared: DEFAULT_SHARED_DECIMALS, deployed: false })
+}
+
+/// Calls a no-argument view returning a single uint.
+async fn view_uint(client: &EvmClient, address: Address, name: &str) -> Result<U256> {
+    let output = client.call(address, &ethabi::short_signature(name, &[])).await?;
+    let decoded = ethabi::decode(&[ParamType::Uint(256)], &output)
+        .with_context(|| format!("{:?} does not implement {}()", address, name))?;
+    Ok(decoded[0].clone().into_uint().expect("uint"))
+}
+
+/// Calls a view function from the artifact ABI and decodes its outputs.
+async fn view(client: &EvmClient, abi: &Contract, address: Address, name: &str) -> Result<Vec<Token>> {
+    let function = abi.function(name)?;
+    let output = client.call(address, &function.encode_input(&[])?).await?;
+    function.decode_output(&output).with_context(|| format!("Could not decode {}() output", name))
+}
+
+/// Reads back `owner()`, `endpoint()` and either `name()`/`symbol()` (OFT) or `token()`
+/// (adapters), and fails on any mismatch.
+async fn verify_deployment(
+    client: &EvmClient,
+    artifact: &Artifact,
+    address: Address,
+    profile: &EvmProfile,
+    params: &OftParams,
+) -> Result<()> {
+    let mut expected = match params.flavour.inner_token()? {
+        None => vec![
+            ("name", Token::String(params.name.clone())),
+            ("symbol", Token::String(params.symbol.clone())),
+        ],
+        Some(token) => vec![("token", Token::Address(token))],
+    };
+    expected.push(("owner", Token::Address(client.address()?)));
+    expected.push(("endpoint", Token::Address(evm_client::parse_address(&profile.endpoint)?)));
+
+    let mut mismatches = Vec::new();
+    for (name, want) in expected {
+        let got = view(client, &artifact.abi, address, name).await?;
+        if got.first() == Some(&want) {
+            println!("   ✔ {}() = {}", name, want);
+        } else {
+            println!("   ✘ {}() = {:?}, expected {}", name, got, want);
+            mismatches.push(name);
+        }
+    }
+    if !mismatches.is_empty() {
+        anyhow::bail!("Deployed OFT at {:?} does not match: {}", address, mismatches.join(", "));
+    }
+    Ok(())
+}
+
+/// Deploys the OFT contract for `params.flavour`, returning its address (from the receipt)
+/// and the deployment tx hash.
+pub async fn deploy_evm_oft(profile: &EvmProfile, deployer: &KeySource, params: &OftParams) -> Result<(String, String)> {
+    let contract = params.flavour.contract_name();
+    match params.flavour {
+        OftFlavour::Oft => println!("🛠️  Deploying OFT '{}' ({}) on {} (chain ID {})...", params.name, params.symbol, profile.name, profile.chain_id),
+        _ => println!("🛠️  Deploying {} on {} (chain ID {})...", contract, profile.name, profile.chain_id),
+    }
+
+    let client = EvmClient::with_signer(&profile.rpc_url, profile.chain_id, deployer.evm_signer().await?);
+    check_flavour(&client, &params.flavour).await?;
+    let artifact = load_artifact(&params.flavour.artifact_path())?;
+    let data = deploy_data(&artifact, &client, profile, params)?;
+    let receipt = client
+        .send_transaction(None, data, U256::zero())
+        .await
+        .with_context(|| format!("{} deployment failed", contract))?;
+    let address = receipt
+        .contract_address
+        .context("Deployment receipt has no contractAddress")?;
+
+    println!("✅ {} deployed! Tx: {} (gas used {})", contract, receipt.tx_hash, receipt.gas_used);
+    println!("🔎 Verifying deployed metadata...");
+    verify_deployment(&client, &artifact, address, profile, params).await?;
+    Ok((format!("{:?}", address), receipt.tx_hash))
+}
+
+/// Estimates the deployment without sending it, returning the address the OFT would get.
+pub async fn simulate_deploy_evm_oft(profile: &EvmProfile, deployer: &KeySource, params: &OftParams) -> Result<String> {
+    let contract = params.flavour.contract_name();
+    println!("🧪 Simulating {} deployment on {} (chain ID {})...", contract, profile.name, profile.chain_id);
+
+    let client = EvmClient::with_signer(&profile.rpc_url, profile.chain_id, deployer.evm_signer().await?);
+    check_flavour(&client, &params.flavour).await?;
+    let artifact = load_artifact(&params.flavour.artifact_path())?;
+    let data = deploy_data(&artifact, &client, profile, params)?;
+    let gas = client
+        .estimate_gas(None, &data, U256::zero())
+        .await
+        .with_context(|| format!("{} deployment would fail", contract))?;
+    let address = client.create_address(client.nonce().await?)?;
+
+    println!("   Estimated gas: {}", gas);
+    Ok(format!("{:?}", address))
+}
+
+/// ABI-encoded calldata for `setPeer(uint32,bytes32)`.
+pub fn set_peer_calldata(target_eid: u32, peer: [u8; 32]) -> Vec<u8> {
+    let mut data = ethabi::short_signature("setPeer", &[ParamType::Uint(32), ParamType::FixedBytes(32)]).to_vec();
+    data.extend(ethabi::encode(&[Token::Uint(target_eid.into()), Token::FixedBytes(peer.to_vec())]));
+    data
+}
+
+/// ABI-encoded calldata for `peers(uint32)`.
+fn peers_calldata(eid: u32) -> Vec<u8> {
+    let mut data = ethabi::short_signature("peers", &[ParamType::Uint(32)]).to_vec();
+    data.extend(ethabi::encode(&[Token::Uint(eid.into())]));
+    data
+}
+
+/// Reads `peers(eid)` from a deployed OApp; `None` if the contract has no code.
+pub async fn read_peer(profile: &EvmProfile, oft_addr: &str, eid: u32) -> Result<Option<String>> {
+    let client = EvmClient::new(&profile.rpc_url, profile.chain_id);
+    let oft = evm_client::parse_address(oft_addr)?;
+    if client.code(oft).await?.is_empty() {
+        return Ok(None);
+    }
+
+    let result = client.call(oft, &peers_calldata(eid)).await?;
+    Ok(Some(format!("0x{}", hex::encode(result))))
+}
+
+/// `setPeer(target_eid, peer)`, returning the mined tx hash unless it was exported.
+pub async fn set_peer_evm(profile: &EvmProfile, submit: &mut Submit<'_>, oft_addr: &str, target_eid: u32, peer: [u8; 32]) -> Result<Option<String>> {
+    println!("🔗 Wiring EVM {} -> EID {} (peer 0x{})...", oft_addr, target_eid, hex::encode(peer));
+    let check = Check::evm_call(oft_addr, peers_calldata(target_eid), &[Token::FixedBytes(peer.to_vec())]);
+    submit.evm(profile, oft_addr, set_peer_calldata(target_eid, peer), "setPeer", vec![check]).await
+}
+
+/// ABI-encoded calldata for `setEnforcedOptions((uint32,uint16,bytes)[])`.
+pub fn set_enforced_options_calldata(params: &[(u32, u16, Vec<u8>)]) -> Vec<u8> {
+    let param_type = ParamType::Tuple(vec![ParamType::Uint(32), ParamType::Uint(16), ParamType::Bytes]);
+    let mut data = ethabi::short_signature("setEnforcedOptions", &[ParamType::Array(Box::new(param_type))]).to_vec();
+    let entries = params
+        .iter()
+        .map(|(eid, msg_type, options)| Token::Tuple(vec![Token::Uint((*eid).into()), Token::Uint((*msg_type).into()), Token::Bytes(options.clone())]))
+        .collect();
+    data.extend(ethabi::encode(&[Token::Array(entries)]));
+    data
+}
+
+/// ABI-encoded calldata for `enforcedOptions(uint32,uint16)`.
+fn enforced_options_calldata(eid: u32, msg_type: u16) -> Vec<u8> {
+    let mut data = ethabi::short_signature("enforcedOptions", &[ParamType::Uint(32), ParamType::Uint(16)]).to_vec();
+    data.extend(ethabi::encode(&[Token::Uint(eid.into()), Token::Uint(msg_type.into())]));
+    data
+}
+
+/// Reads `enforcedOptions(eid, msgType)`; empty when none are set.
+pub async fn read_enforced_options(profile: &EvmProfile, oft_addr: &str, eid: u32, msg_type: u16) -> Result<Vec<u8>> {
+    let client = EvmClient::new(&profile.rpc_url, profile.chain_id);
+    let oft = evm_client::parse_address(oft_addr)?;
+    let output = client.call(oft, &enforced_options_calldata(eid, msg_type)).await?;
+    let decoded = ethabi::decode(&[ParamType::Bytes], &output)
+        .with_context(|| format!("{} does not implement enforcedOptions()", oft_addr))?;
+    Ok(decoded[0].clone().into_bytes().expect("bytes"))
+}
+
+/// Sets the enforced `SEND` and `SEND_AND_CALL` options for `eid` unless they already match,
+/// returning the tx hash if one was sent.
+pub async fn set_enforced_options_evm(profile: &EvmProfile, submit: &mut Submit<'_>, oft_addr: &str, eid: u32, spec: &EnforcedOptionsSpec) -> Result<Option<String>> {
+    let (send, send_and_call) = (spec.send(), spec.send_and_call());
+    options::assert_type_3(&send)?;
+    options::assert_type_3(&send_and_call)?;
+    if read_enforced_options(profile, oft_addr, eid, options::MSG_TYPE_SEND).await? == send
+        && read_enforced_options(profile, oft_addr, eid, options::MSG_TYPE_SEND_AND_CALL).await? == send_and_call
+    {
+        println!("⏭️  Enforced options for EID {} already set", eid);
+        return Ok(None);
+    }
+
+    println!("⚙️  Enforcing options on EVM {} -> EID {} (send 0x{}, send+call 0x{})...", oft_addr, eid, hex::encode(&send), hex::encode(&send_and_call));
+    let checks = vec![
+        Check::evm_call(oft_addr, enforced_options_calldata(eid, options::MSG_TYPE_SEND), &[Token::Bytes(send.clone())]),
+        Check::evm_call(oft_addr, enforced_options_calldata(eid, options::MSG_TYPE_SEND_AND_CALL), &[Token::Bytes(send_and_call.clone())]),
+    ];
+    let calldata = set_enforced_options_calldata(&[
+        (eid, options::MSG_TYPE_SEND, send),
+        (eid, options::MSG_TYPE_SEND_AND_CALL, send_and_call),
+    ]);
+    submit.evm(profile, oft_addr, calldata, "setEnforcedOptions", checks).await
+}
+
+/// `Fee.defaultFeeBps()`; fails if the contract does not implement `IFee`.
+pub async fn read_default_fee_bps(profile: &EvmProfile, oft_addr: &str) -> Result<u16> {
+    let client = EvmClient::new(&profile.rpc_url, profile.chain_id);
+    let oft = evm_client::parse_address(oft_addr)?;
+    let bps = view_uint(&client, oft, "defaultFeeBps").await.context("The OFT does not implement IFee (Fee.sol)")?;
+    Ok(bps.low_u32() as u16)
+}
+
+fn fee_bps_calldata(eid: u32) -> Vec<u8> {
+    let mut data = ethabi::short_signature("feeBps", &[ParamType::Uint(32)]).to_vec();
+    data.extend(ethabi::encode(&[Token::Uint(eid.into())]));
+    data
+}
+
+/// `Fee.feeBps(dstEid)` as `(fee_bps, enabled)`.
+pub async fn read_fee_bps(profile: &EvmProfile, oft_addr: &str, eid: u32) -> Result<(u16, bool)> {
+    let client = EvmClient::new(&profile.rpc_url, profile.chain_id);
+    let oft = evm_client::parse_address(oft_addr)?;
+    let output = client.call(oft, &fee_bps_calldata(eid)).await?;
+    let decoded = ethabi::decode(&[ParamType::Uint(16), ParamType::Bool], &output)
+        .context("The OFT does not implement IFee (Fee.sol)")?;
+    Ok((decoded[0].clone().into_uint().expect("uint").low_u32() as u16, decoded[1].clone().into_bool().expect("bool")))
+}
+
+pub async fn set_default_fee_bps_evm(profile: &EvmProfile, submit: &mut Submit<'_>, oft_addr: &str, bps: u16) -> Result<Option<String>> {
+    oft_client::validate_fee_bps(bps)?;
+    read_default_fee_bps(profile, oft_addr).await?;
+    println!("💸 Setting EVM {} default fee to {} bps...", oft_addr, bps);
+    let mut data = ethabi::short_signature("setDefaultFeeBps", &[ParamType::Uint(16)]).to_vec();
+    data.extend(ethabi::encode(&[Token::Uint(bps.into())]));
+    let check = Check::evm_call(oft_addr, ethabi::short_signature("defaultFeeBps", &[]).to_vec(), &[Token::Uint(bps.into())]);
+    submit.evm(profile, oft_addr, data, "setDefaultFeeBps", vec![check]).await
+}
+
+/// `setFeeBps(eid, bps, true)`, or `setFeeBps(eid, 0, false)` to fall back to the default fee.
+pub async fn set_fee_bps_evm(profile: &EvmProfile, submit: &mut Submit<'_>, oft_addr: &str, eid: u32, bps: Option<u16>) -> Result<Option<String>> {
+    if let Some(bps) = bps {
+        oft_client::validate_fee_bps(bps)?;
+    }
+    read_default_fee_bps(profile, oft_addr).await?;
+    match bps {
+        Some(bps) => println!("💸 Setting EVM {} fee for EID {} to {} bps...", oft_addr, eid, bps),
+        None => println!("💸 Clearing EVM {} fee for EID {}...", oft_addr, eid),
+    }
+    let mut data = ethabi::short_signature("setFeeBps", &[ParamType::Uint(32), ParamType::Uint(16), ParamType::Bool]).to_vec();
+    data.extend(ethabi::encode(&[Token::Uint(eid.into()), Token::Uint(bps.unwrap_or(0).into()), Token::Bool(bps.is_some())]));
+    let check = Check::evm_call(oft_addr, fee_bps_calldata(eid), &[Token::Uint(bps.unwrap_or(0).into()), Token::Bool(bps.is_some())]);
+    submit.evm(profile, oft_addr, data, "setFeeBps", vec![check]).await
+}
+
+/// OpenZeppelin `Pausable.paused()`, or `None` when the call reverts or does not return a
+/// bool (not pausable). RPC failures are errors.
+pub async fn read_paused(profile: &EvmProfile, oft_addr: &str) -> Result<Option<bool>> {
+    let client = EvmClient::new(&profile.rpc_url, profile.chain_id);
+    let oft = evm_client::parse_address(oft_addr)?;
+    let Some(output) = client.try_call(oft, &ethabi::short_signature("paused", &[])).await? else {
+        return Ok(None);
+    };
+    Ok(ethabi::decode(&[ParamType::Bool], &output).ok().and_then(|decoded| decoded[0].clone().into_bool()))
+}
+
+/// `pause()` or `unpause()` on a pausable OFT; the signer needs the contract's pauser role.
+pub async fn set_paused_evm(profile: &EvmProfile, submit: &mut Submit<'_>, oft_addr: &str, paused: bool) -> Result<Option<String>> {
+    let function = if paused { "pause" } else { "unpause" };
+    let check = Check::evm_call(oft_addr, ethabi::short_signature("paused", &[]).to_vec(), &[Token::Bool(paused)]);
+    submit.evm(profile, oft_addr, ethabi::short_signature(function, &[]).to_vec(), function, vec![check]).await
+}
+
+async fn view_address(client: &EvmClient, address: Address, data: &[u8], name: &str) -> Result<Address> {
+    let output = client.call(address, data).await?;
+    let decoded = ethabi::decode(&[ParamType::Address], &output)
+        .with_context(|| format!("{:?} does not implement {}()", address, name))?;
+    Ok(decoded[0].clone().into_address().expect("address"))
+}
+
+fn delegates_calldata(oapp: Address) -> Vec<u8> {
+    let mut data = ethabi::short_signature("delegates", &[ParamType::Address]).to_vec();
+    data.extend(ethabi::encode(&[Token::Address(oapp)]));
+    data
+}
+
+/// `Ownable.owner()` of the OFT.
+pub async fn read_owner(profile: &EvmProfile, oft_addr: &str) -> Result<Address> {
+    let client = EvmClient::new(&profile.rpc_url, profile.chain_id);
+    let oft = evm_client::parse_address(oft_addr)?;
+    view_address(&client, oft, &ethabi::short_signature("owner", &[]), "owner").await
+}
+
+/// `owner()` of the OFT and its delegate on the endpoint, `EndpointV2.delegates(oft)`.
+pub async fn read_ownership(profile: &EvmProfile, oft_addr: &str) -> Result<(Address, Address)> {
+    let client = EvmClient::new(&profile.rpc_url, profile.chain_id);
+    let oft = evm_client::parse_address(oft_addr)?;
+    let endpoint = evm_client::parse_address(&profile.endpoint)?;
+    let owner = read_owner(profile, oft_addr).await?;
+    let delegate = view_address(&client, endpoint, &delegates_calldata(oft), "delegates").await?;
+    Ok((owner, delegate))
+}
+
+/// Moves the endpoint delegate (`setDelegate`) and then `owner` (`transferOwnership`) to
+/// `new_owner`, and reads both back unless exported. Without `force`, `new_owner` must be a
+/// contract (e.g. a Safe), since an EOA typo would lock the OFT out.
+pub async fn transfer_ownership_evm(profile: &EvmProfile, submit: &mut Submit<'_>, oft_addr: &str, new_owner: Address, force: bool) -> Result<()> {
+    let client = EvmClient::new(&profile.rpc_url, profile.chain_id);
+    let oft = evm_client::parse_address(oft_addr)?;
+    let (owner, delegate) = read_ownership(profile, oft_addr).await?;
+    println!("   owner               {:?}", owner);
+    println!("   delegate            {:?}", delegate);
+    if owner == new_owner && delegate == new_owner {
+        println!("⏭️  {} is already owned by {:?}", oft_addr, new_owner);
+        return Ok(());
+    }
+    if new_owner.is_zero() {
+        anyhow::bail!("Refusing to transfer ownership to the zero address");
+    }
+    if let Submit::Sign(key) = submit {
+        let me = key.evm_signer().await?.address();
+        if owner != me {
+            anyhow::bail!("Signer {:?} is not the owner ({:?}) of {}", me, owner, oft_addr);
+        }
+    }
+    if client.code(new_owner).await?.is_empty() && !force {
+        anyhow::bail!("{:?} has no code on {}; pass --force to transfer to an EOA", new_owner, profile.name);
+    }
+
+    if delegate != new_owner {
+        println!("🔑 Setting endpoint delegate of {} to {:?}...", oft_addr, new_owner);
+        let mut data = ethabi::short_signature("setDelegate", &[ParamType::Address]).to_vec();
+        data.extend(ethabi::encode(&[Token::Address(new_owner)]));
+        let check = Check::evm_call(&profile.endpoint, delegates_calldata(oft), &[Token::Address(new_owner)]);
+        submit.evm(profile, oft_addr, data, "setDelegate", vec![check]).await?;
+    }
+    if owner != new_owner {
+        println!("🔑 Transferring ownership of {} to {:?}...", oft_addr, new_owner);
+        let mut data = ethabi::short_signature("transferOwnership", &[ParamType::Address]).to_vec();
+        data.extend(ethabi::encode(&[Token::Address(new_owner)]));
+        let check = Check::evm_call(oft_addr, ethabi::short_signature("owner", &[]).to_vec(), &[Token::Address(new_owner)]);
+        submit.evm(profile, oft_addr, data, "transferOwnership", vec![check]).await?;
+    }
+    if submit.is_export() {
+        return Ok(());
+    }
+
+    let (owner, delegate) = read_ownership(profile, oft_addr).await?;
+    if owner != new_owner || delegate != new_owner {
+        anyhow::bail!("Read-back mismatch on {}: owner {:?}, delegate {:?}, expected {:?}", oft_addr, owner, delegate, new_owner);
+    }
+    println!("   ✔ owner() = delegates() = {:?}", new_owner);
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Hands owner and endpoint delegate over to `params.delegate` after wiring; a no-op without
+/// one. The address was given explicitly, so it may be an EOA.
+pub async fn hand_over_evm(profile: &EvmProfile, submit: &mut Submit<'_>, oft_addr: &str, params: &OftParams) -> Result<()> {
+    let Some(delegate) = &params.delegate else {
+        return Ok(());
+    };
+    println!("👑 Handing {} over to delegate {}...", oft_addr, delegate);
+    transfer_ownership_evm(profile, submit, oft_addr, evm_client::parse_address(delegate)?, true).await
+}
+
+/// `IOFT.SendParam` without a compose message or OFT command.
+pub struct SendParam {
+    pub dst_eid: u32,
+    pub to: [u8; 32],
+    pub amount_ld: U256,
+    pub min_amount_ld: U256,
+    pub extra_options: Vec<u8>,
+}
+
+impl SendParam {
+    fn param_type() -> ParamType {
+        ParamType::Tuple(vec![
+            ParamType::Uint(32),
+            ParamType::FixedBytes(32),
+            ParamType::Uint(256),
+            ParamType::Uint(256),
+            ParamType::Bytes,
+            ParamType::Bytes,
+            ParamType::Bytes,
+        ])
+    }
+
+    fn token(&self) -> Token {
+        Token::Tuple(vec![
+            Token::Uint(self.dst_eid.into()),
+            Token::FixedBytes(self.to.to_vec()),
+            Token::Uint(self.amount_ld),
+            Token::Uint(self.min_amount_ld),
+            Token::Bytes(self.extra_options.clone()),
+            Token::Bytes(Vec::new()),
+            Token::Bytes(Vec::new()),
+        ])
+    }
+}
+
+/// `quoteOFT` result. Fee detail amounts are signed: negative ones are rewards.
+pub struct OftQuote {
+    pub min_amount_ld: U256,
+    pub max_amount_ld: U256,
+    pub fee_details: Vec<(i128, String)>,
+    pub amount_sent_ld: U256,
+    pub amount_received_ld: U256,
+}
+
+/// `MessagingFee(nativeFee, lzTokenFee)`.
+fn messaging_fee_type() -> ParamType {
+    ParamType::Tuple(vec![ParamType::Uint(256), ParamType::Uint(256)])
+}
+
+/// Two's complement `int256` as `i128`, saturating.
+fn int256_to_i128(value: U256) -> i128 {
+    let max = U256::from(i128::MAX as u128);
+    if value.bit(255) {
+        -((!value + 1).min(max).as_u128() as i128)
+    } else {
+        value.min(max).as_u128() as i128
+    }
+}
+
+fn uint_fields(token: Token) -> Vec<U256> {
+    token.into_tuple().expect("tuple").into_iter().map(|t| t.into_uint().expect("uint")).collect()
+}
+
+pub async fn quote_oft_evm(profile: &EvmProfile, oft_addr: &str, param: &SendParam) -> Result<OftQuote> {
+    let client = EvmClient::new(&profile.rpc_url, profile.chain_id);
+    let oft = evm_client::parse_address(oft_addr)?;
+    let mut data = ethabi::short_signature("quoteOFT", &[SendParam::param_type()]).to_vec();
+    data.extend(ethabi::encode(&[param.token()]));
+    let output = client.call(oft, &data).await.context("quoteOFT failed")?;
+
+    let pair = ParamType::Tuple(vec![ParamType::Uint(256), ParamType::Uint(256)]);
+    let fee_detail = ParamType::Tuple(vec![ParamType::Int(256), ParamType::String]);
+    let mut decoded = ethabi::decode(&[pair.clone(), ParamType::Array(Box::new(fee_detail)), pair], &output)
+        .context("Could not decode quoteOFT output")?
+        .into_iter();
+    let limits = uint_fields(decoded.next().expect("limits"));
+    let fee_details = decoded
+        .next()
+        .expect("fee details")
+        .into_array()
+        .expect("array")
+        .into_iter()
+        .map(|detail| {
+            let mut fields = detail.into_tuple().expect("tuple").into_iter();
+            let amount = int256_to_i128(fields.next().expect("amount").into_int().expect("int"));
+            (amount, fields.next().expect("description").into_string().expect("string"))
+        })
+        .collect();
+    let receipt = uint_fields(decoded.next().expect("receipt"));
+    Ok(OftQuote {
+        min_amount_ld: limits[0],
+        max_amount_ld: limits[1],
+        fee_details,
+        amount_sent_ld: receipt[0],
+        amount_received_ld: receipt[1],
+    })
+}
+
+/// Native fee from `quoteSend(param, false)`.
+pub async fn quote_send_evm(profile: &EvmProfile, oft_addr: &str, param: &SendParam) -> Result<U256> {
+    let client = EvmClient::new(&profile.rpc_url, profile.chain_id);
+    let oft = evm_client::parse_address(oft_addr)?;
+    let mut data = ethabi::short_signature("quoteSend", &[SendParam::param_type(), ParamType::Bool]).to_vec();
+    data.extend(ethabi::encode(&[param.token(), Token::Bool(false)]));
+    let output = client.call(oft, &data).await.context("quoteSend failed")?;
+    let decoded = ethabi::decode(&[messaging_fee_type()], &output).context("Could not decode quoteSend output")?;
+    Ok(uint_fields(decoded[0].clone())[0])
+}
+
+async fn erc20_uint(client: &EvmClient, token: Address, name: &str, args: &[Token]) -> Result<U256> {
+    let types: Vec<ParamType> = args.iter().map(|_| ParamType::Address).collect();
+    let mut data = ethabi::short_signature(name, &types).to_vec();
+    data.extend(ethabi::encode(args));
+    let output = client.call(token, &data).await?;
+    let decoded = ethabi::decode(&[ParamType::Uint(256)], &output)
+        .with_context(|| format!("{:?} does not implement {}()", token, name))?;
+    Ok(decoded[0].clone().into_uint().expect("uint"))
+}
+
+/// Sends `param` from the signer, paying `native_fee`, and returns the tx hash and the GUID
+/// from the `OFTSent` event. Adapters whose `approvalRequired()` is set get an `approve` for
+/// `amount_sent_ld` first; native adapters (`token()` is zero) also take it as value.
+pub async fn send_evm(profile: &EvmProfile, key: &KeySource, oft_addr: &str, param: &SendParam, amount_sent_ld: U256, native_fee: U256) -> Result<(String, [u8; 32])> {
+    let client = EvmClient::with_signer(&profile.rpc_url, profile.chain_id, key.evm_signer().await?);
+    let oft = evm_client::parse_address(oft_addr)?;
+    let me = client.address()?;
+    let token = view_address(&client, oft, &ethabi::short_signature("token", &[]), "token").await?;
+
+    let mut value = native_fee;
+    if token.is_zero() {
+        value += amount_sent_ld;
+    } else {
+        let balance = erc20_uint(&client, token, "balanceOf", &[Token::Address(me)]).await?;
+        if balance < amount_sent_ld {
+            anyhow::bail!("{:?} holds {} of token {:?}, less than the {} sent", me, balance, token, amount_sent_ld);
+        }
+        let output = client.call(oft, &ethabi::short_signature("approvalRequired", &[])).await?;
+        let approval_required = ethabi::decode(&[ParamType::Bool], &output).is_ok_and(|d| d[0].clone().into_bool().expect("bool"));
+        let allowance = erc20_uint(&client, token, "allowance", &[Token::Address(me), Token::Address(oft)]).await?;
+        if approval_required && allowance < amount_sent_ld {
+            println!("🔓 Approving {} of {:?} for {}...", amount_sent_ld, token, oft_addr);
+            let mut data = ethabi::short_signature("approve", &[ParamType::Address, ParamType::Uint(256)]).to_vec();
+            data.extend(ethabi::encode(&[Token::Address(oft), Token::Uint(amount_sent_ld)]));
+            let receipt = client.send_transaction(Some(token), data, U256::zero()).await.context("approve failed")?;
+            println!("✅ approve Tx: {}", receipt.tx_hash);
+        }
+    }
+
+    println!("🚀 Sending from {:?}...", me);
+    let mut data = ethabi::short_signature("send", &[SendParam::param_type(), messaging_fee_type(), ParamType::Address]).to_vec();
+    data.extend(ethabi::encode(&[
+        param.token(),
+        Token::Tuple(vec![Token::Uint(native_fee), Token::Uint(U256::zero())]),
+        Token::Address(me),
+    ]));
+    let receipt = client.send_transaction(Some(oft), data, value).await.context("send failed")?;
+    let oft_sent = ethabi::long_signature(
+        "OFTSent",
+        &[ParamType::FixedBytes(32), ParamType::Uint(32), ParamType::Address, ParamType::Uint(256), ParamType::Uint(256)],
+    );
+    let guid = receipt
+        .logs
+        .iter()
+        .find(|log| log.address == oft && log.topics.first().map(Vec::as_slice) == Some(oft_sent.as_bytes()))
+        .and_then(|log| log.topics.get(1))
+        .with_context(|| format!("No OFTSent event in {}", receipt.tx_hash))?;
+    Ok((receipt.tx_hash, guid.as_slice().try_into().context("GUID topic is not 32 bytes")?))
+}
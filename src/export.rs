@@ -0,0 +1,500 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+use base64::Engine;
+use ethabi::{ethereum_types::{Address, U256}, Token};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use solana_sdk::{bs58, hash::Hash, instruction::Instruction, message::Message, pubkey::Pubkey, signature::Signature};
+use anyhow::{Result, Context};
+use crate::evm_client::{self, EvmClient};
+use crate::network::{EvmProfile, SolanaProfile};
+use crate::oft_client::{OftClient, RateLimiter};
+use crate::signer::KeySource;
+use crate::evm_ops;
+
+/// Index of everything exported into a directory; the Safe and Squads files are rebuilt from it.
+pub const ACTIONS_FILE: &str = "actions.json";
+
+/// `txBuilderVersion` the Safe batch files are written for.
+const SAFE_TX_BUILDER_VERSION: &str = "1.16.5";
+
+#[derive(clap::Args)]
+pub struct ExportArgs {
+    /// Write admin actions to this directory as unsigned multisig proposals (Safe batch JSON
+    /// for EVM, Squads messages for Solana) instead of signing them
+    #[arg(long, value_name = "DIR")]
+    pub export_unsigned: Option<PathBuf>,
+}
+
+impl ExportArgs {
+    pub fn exporter(&self) -> Result<Option<Exporter>> {
+        self.export_unsigned.as_deref().map(Exporter::open).transpose()
+    }
+}
+
+/// Unsigned transaction of one admin action.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum UnsignedTx {
+    /// Call from the Safe that owns the OFT.
+    Evm { safe: String, to: String, value: String, data: String },
+    /// Message paid and signed by `authority` (a Squads vault). The blockhash is a placeholder
+    /// that Squads replaces when it executes the vault transaction.
+    Solana { authority: String, message_base58: String, message_base64: String },
+}
+
+/// State an action leaves behind, read back by `verify-executed`.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum Check {
+    /// `eth_call` of `data` on `to`, whose return data must equal `expected`.
+    EvmCall { to: String, data: String, expected: String },
+    SolanaStore { store: String, field: StoreField, expected: String },
+    SolanaPeer { store: String, eid: u32, field: PeerField, expected: String },
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StoreField {
+    Admin,
+    Delegate,
+    Paused,
+    Pauser,
+    Unpauser,
+    DefaultFeeBps,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PeerField {
+    PeerAddress,
+    FeeBps,
+    EnforcedOptions,
+    OutboundRateLimit,
+    InboundRateLimit,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ExportedAction {
+    pub chain: String,
+    pub description: String,
+    pub tx: UnsignedTx,
+    pub checks: Vec<Check>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct Exported {
+    #[serde(default)]
+    pub solana: BTreeMap<String, SolanaProfile>,
+    #[serde(default)]
+    pub evm: BTreeMap<String, EvmProfile>,
+    pub actions: Vec<ExportedAction>,
+}
+
+impl Exported {
+    pub fn load(dir: &Path) -> Result<Self> {
+        let path = dir.join(ACTIONS_FILE);
+        let raw = std::fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&raw).with_context(|| format!("Invalid {}", path.display()))
+    }
+}
+
+/// Collects admin actions into a directory, appending to what an earlier run exported there.
+pub struct Exporter {
+    dir: PathBuf,
+    exported: Exported,
+}
+
+impl Check {
+    pub fn evm_call(to: &str, data: Vec<u8>, expected: &[Token]) -> Check {
+        Check::EvmCall { to: to.to_string(), data: format!("0x{}", hex::encode(data)), expected: format!("0x{}", hex::encode(ethabi::encode(expected))) }
+    }
+
+    pub fn store(store: &Pubkey, field: StoreField, expected: impl ToString) -> Check {
+        Check::SolanaStore { store: store.to_string(), field, expected: expected.to_string() }
+    }
+
+    pub fn peer(store: &Pubkey, eid: u32, field: PeerField, expected: impl ToString) -> Check {
+        Check::SolanaPeer { store: store.to_string(), eid, field, expected: expected.to_string() }
+    }
+}
+
+/// Forms compared by the Solana checks.
+pub fn fmt_key(key: Option<Pubkey>) -> String {
+    key.map_or("none".to_string(), |k| k.to_string())
+}
+
+pub fn fmt_fee_bps(bps: Option<u16>) -> String {
+    bps.map_or("default".to_string(), |b| b.to_string())
+}
+
+pub fn fmt_enforced_options(send: &[u8], send_and_call: &[u8]) -> String {
+    format!("send 0x{}, send+call 0x{}", hex::encode(send), hex::encode(send_and_call))
+}
+
+/// `(capacity, refill_per_second)` of a limiter, or unlimited.
+pub fn fmt_rate_limit(limit: Option<(u64, u64)>) -> String {
+    limit.map_or("unlimited".to_string(), |(capacity, refill)| format!("capacity {}, +{}/s", capacity, refill))
+}
+
+fn limiter_params(limiter: &Option<RateLimiter>) -> Option<(u64, u64)> {
+    limiter.as_ref().map(|l| (l.capacity, l.refill_per_second))
+}
+
+impl Exporter {
+    pub fn open(dir: &Path) -> Result<Self> {
+        let exported = if dir.join(ACTIONS_FILE).exists() { Exported::load(dir)? } else { Exported::default() };
+        Ok(Exporter { dir: dir.to_path_buf(), exported })
+    }
+
+    pub fn evm(&mut self, profile: &EvmProfile, safe: Address, to: &str, data: Vec<u8>, description: &str, checks: Vec<Check>) -> Result<()> {
+        let safe = format!("{:?}", safe);
+        for action in self.exported.actions.iter().filter(|a| a.chain == profile.name) {
+            if let UnsignedTx::Evm { safe: other, .. } = &action.tx {
+                if !other.eq_ignore_ascii_case(&safe) {
+                    anyhow::bail!("{} already has actions for Safe {}, not {}; export to another directory", profile.name, other, safe);
+                }
+            }
+        }
+        self.exported.evm.insert(profile.name.clone(), profile.clone());
+        self.exported.actions.push(ExportedAction {
+            chain: profile.name.clone(),
+            description: description.to_string(),
+            tx: UnsignedTx::Evm { safe, to: to.to_string(), value: "0".to_string(), data: format!("0x{}", hex::encode(data)) },
+            checks,
+        });
+        self.save(&profile.name, "safe")
+    }
+
+    pub fn solana(&mut self, chain: &str, profile: &SolanaProfile, authority: &Pubkey, instructions: &[Instruction], description: &str, checks: Vec<Check>) -> Result<()> {
+        let mut message = Message::new(instructions, Some(authority));
+        message.recent_blockhash = Hash::default();
+        let bytes = message.serialize();
+        self.exported.solana.insert(chain.to_string(), profile.clone());
+        self.exported.actions.push(ExportedAction {
+            chain: chain.to_string(),
+            description: description.to_string(),
+            tx: UnsignedTx::Solana {
+                authority: authority.to_string(),
+                message_base58: bs58::encode(&bytes).into_string(),
+                message_base64: base64::engine::general_purpose::STANDARD.encode(&bytes),
+            },
+            checks,
+        });
+        self.save(chain, "squads")
+    }
+
+    /// Rewrites the index and the proposal file of `chain`, so every export is usable as is.
+    fn save(&self, chain: &str, kind: &str) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        std::fs::write(self.dir.join(ACTIONS_FILE), serde_json::to_string_pretty(&self.exported)?)?;
+        let actions: Vec<&ExportedAction> = self.exported.actions.iter().filter(|a| a.chain == chain).collect();
+        let file = if kind == "safe" { self.safe_batch(chain, &actions) } else { squads_batch(chain, &actions) };
+        let path = self.dir.join(format!("{}.{}.json", chain, kind));
+        std::fs::write(&path, serde_json::to_string_pretty(&file)?)?;
+        println!("📝 Exported unsigned: {} -> {}", actions.last().map_or("", |a| a.description.as_str()), path.display());
+        Ok(())
+    }
+
+    /// Safe Transaction Builder batch, importable from the Safe web app.
+    fn safe_batch(&self, chain: &str, actions: &[&ExportedAction]) -> serde_json::Value {
+        let mut safe = "";
+        let transactions: Vec<serde_json::Value> = actions
+            .iter()
+            .filter_map(|a| match &a.tx {
+                UnsignedTx::Evm { safe: s, to, value, data } => {
+                    safe = s;
+                    Some(json!({ "to": to, "value": value, "data": data, "contractMethod": null, "contractInputsValues": null }))
+                }
+                UnsignedTx::Solana { .. } => None,
+            })
+            .collect();
+        let descriptions: Vec<&str> = actions.iter().map(|a| a.description.as_str()).collect();
+        let created_at = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_millis() as u64);
+        json!({
+            "version": "1.0",
+            "chainId": self.exported.evm[chain].chain_id.to_string(),
+            "createdAt": created_at,
+            "meta": {
+                "name": format!("oft-cli {}", chain),
+                "description": descriptions.join("; "),
+                "txBuilderVersion": SAFE_TX_BUILDER_VERSION,
+                "createdFromSafeAddress": safe,
+                "createdFromOwnerAddress": "",
+            },
+            "transactions": transactions,
+        })
+    }
+}
+
+/// One Squads vault transaction per action, each a serialized message to import.
+fn squads_batch(chain: &str, actions: &[&ExportedAction]) -> serde_json::Value {
+    let transactions: Vec<serde_json::Value> = actions
+        .iter()
+        .filter_map(|a| match &a.tx {
+            UnsignedTx::Solana { authority, message_base58, message_base64 } => Some(json!({
+                "description": a.description,
+                "authority": authority,
+                "message_base58": message_base58,
+                "message_base64": message_base64,
+            })),
+            UnsignedTx::Evm { .. } => None,
+        })
+        .collect();
+    json!({ "chain": chain, "transactions": transactions })
+}
+
+/// Who carries out an admin action: a local key signs and sends it, or it is exported unsigned
+/// for the on-chain authority to propose from its multisig.
+pub enum Submit<'a> {
+    Sign(KeySource),
+    Export(&'a mut Exporter),
+}
+
+impl Submit<'_> {
+    /// Exports into `exporter` if there is one; otherwise signs with the key from `key`.
+    pub fn new(exporter: &mut Option<Exporter>, key: impl FnOnce() -> Result<KeySource>) -> Result<Submit<'_>> {
+        Ok(match exporter {
+            Some(exporter) => Submit::Export(exporter),
+            None => Submit::Sign(key()?),
+        })
+    }
+
+    pub fn is_export(&self) -> bool {
+        matches!(self, Submit::Export(_))
+    }
+
+    /// Signer of Solana instructions: the local key, or the on-chain `authority` when exporting.
+    pub fn solana_authority(&self, authority: impl FnOnce() -> Result<Pubkey>) -> Result<Pubkey> {
+        match self {
+            Submit::Sign(key) => Ok(key.solana_signer()?.pubkey()),
+            Submit::Export(_) => authority(),
+        }
+    }
+
+    /// Sends `instructions` signed by the local key, returning the signature, or exports them.
+    pub fn solana(
+        &mut self,
+        client: &OftClient,
+        chain: &str,
+        authority: &Pubkey,
+        instructions: &[Instruction],
+        description: &str,
+        checks: Vec<Check>,
+    ) -> Result<Option<Signature>> {
+        match self {
+            Submit::Sign(key) => {
+                let signer = key.solana_signer()?;
+                if signer.pubkey() != *authority {
+                    anyhow::bail!("{} must be signed by {}, not {}", description, authority, signer.pubkey());
+                }
+                let sig = client.send_transaction(instructions, signer.as_ref(), &[]).with_context(|| format!("Failed to send {} tx", description))?;
+                Ok(Some(sig))
+            }
+            Submit::Export(exporter) => {
+                exporter.solana(chain, client.profile(), authority, instructions, description, checks)?;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Sends `data` to `oft_addr` from the local key, returning the mined tx hash, or exports it
+    /// as a call from the OFT's owner, which must be the Safe.
+    pub async fn evm(&mut self, profile: &EvmProfile, oft_addr: &str, data: Vec<u8>, what: &str, checks: Vec<Check>) -> Result<Option<String>> {
+        let oft = evm_client::parse_address(oft_addr)?;
+        match self {
+            Submit::Sign(key) => {
+                let client = EvmClient::with_signer(&profile.rpc_url, profile.chain_id, key.evm_signer().await?);
+                let receipt = client
+                    .send_transaction(Some(oft), data, U256::zero())
+                    .await
+                    .with_context(|| format!("{} failed", what))?;
+                println!("✅ {} Tx: {} (gas used {})", what, receipt.tx_hash, receipt.gas_used);
+                Ok(Some(receipt.tx_hash))
+            }
+            Submit::Export(exporter) => {
+                let safe = evm_ops::read_owner(profile, oft_addr).await?;
+                exporter.evm(profile, safe, oft_addr, data, &format!("{} on {}", what, oft_addr), checks)?;
+                Ok(None)
+            }
+        }
+    }
+}
+
+/// Where the checks of one action are read from.
+enum Reader<'a> {
+    Evm(EvmClient),
+    Solana(&'a OftClient),
+}
+
+/// Current value of a check, in the form its `expected` was written in.
+async fn check_value(check: &Check, reader: &Reader<'_>) -> Result<String> {
+    match (check, reader) {
+        (Check::EvmCall { to, data, .. }, Reader::Evm(client)) => {
+            let data = hex::decode(data.trim_start_matches("0x"))?;
+            Ok(format!("0x{}", hex::encode(client.call(evm_client::parse_address(to)?, &data).await?)))
+        }
+        (Check::SolanaStore { store, field, .. }, Reader::Solana(client)) => {
+            let store = Pubkey::from_str(store)?;
+            let oft_store = client.oft_store(&store)?;
+            Ok(match field {
+                StoreField::Admin => oft_store.admin.to_string(),
+                StoreField::Delegate => client.oapp_delegate(&store)?.to_string(),
+                StoreField::Paused => oft_store.paused.to_string(),
+                StoreField::Pauser => fmt_key(oft_store.pauser),
+                StoreField::Unpauser => fmt_key(oft_store.unpauser),
+                StoreField::DefaultFeeBps => oft_store.default_fee_bps.to_string(),
+            })
+        }
+        (Check::SolanaPeer { store, eid, field, .. }, Reader::Solana(client)) => {
+            let Some(peer) = client.peer_config(&Pubkey::from_str(store)?, *eid)? else {
+                return Ok("no peer config".to_string());
+            };
+            Ok(match field {
+                PeerField::PeerAddress => format!("0x{}", hex::encode(peer.peer_address)),
+                PeerField::FeeBps => fmt_fee_bps(peer.fee_bps),
+                PeerField::EnforcedOptions => fmt_enforced_options(&peer.enforced_options.send, &peer.enforced_options.send_and_call),
+                PeerField::OutboundRateLimit => fmt_rate_limit(limiter_params(&peer.outbound_rate_limiter)),
+                PeerField::InboundRateLimit => fmt_rate_limit(limiter_params(&peer.inbound_rate_limiter)),
+            })
+        }
+        _ => anyhow::bail!("Check {} does not belong to this chain's family", serde_json::to_string(check)?),
+    }
+}
+
+fn expected(check: &Check) -> &str {
+    match check {
+        Check::EvmCall { expected, .. } | Check::SolanaStore { expected, .. } | Check::SolanaPeer { expected, .. } => expected,
+    }
+}
+
+/// Reads back every exported action and reports which ones the multisigs have executed.
+pub async fn verify_executed(dir: &Path) -> Result<()> {
+    let exported = Exported::load(dir)?;
+    println!("🔎 Verifying {} exported action(s) from {}", exported.actions.len(), dir.display());
+    let mut clients = BTreeMap::new();
+    for (chain, profile) in &exported.solana {
+        clients.insert(chain.as_str(), OftClient::new(profile)?);
+    }
+
+    let (mut pending, mut manual) = (0, 0);
+    for action in &exported.actions {
+        if action.checks.is_empty() {
+            manual += 1;
+            println!("➖ [{}] {}: nothing to read back, check manually", action.chain, action.description);
+            continue;
+        }
+        let missing = || format!("No profile for '{}' in {}", action.chain, ACTIONS_FILE);
+        let reader = match &action.tx {
+            UnsignedTx::Evm { .. } => {
+                let evm = exported.evm.get(&action.chain).with_context(missing)?;
+                Reader::Evm(EvmClient::new(&evm.rpc_url, evm.chain_id))
+            }
+            UnsignedTx::Solana { .. } => Reader::Solana(clients.get(action.chain.as_str()).with_context(missing)?),
+        };
+        let mut mismatches = Vec::new();
+        for check in &action.checks {
+            let actual = check_value(check, &reader).await?;
+            if !actual.eq_ignore_ascii_case(expected(check)) {
+                mismatches.push((expected(check).to_string(), actual));
+            }
+        }
+        if mismatches.is_empty() {
+            println!("✅ [{}] {}", action.chain, action.description);
+        } else {
+            pending += 1;
+            println!("⏳ [{}] {}", action.chain, action.description);
+            for (expected, actual) in mismatches {
+                println!("     expected {}, found {}", expected, actual);
+            }
+        }
+    }
+
+    println!("\n   {} executed, {} pending, {} to check manually", exported.actions.len() - pending - manual, pending, manual);
+    if pending > 0 {
+        anyhow::bail!("{} exported action(s) have not landed yet", pending);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::{commitment_config::CommitmentLevel, instruction::AccountMeta};
+
+    fn export_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("oft-export-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn read_json(path: PathBuf) -> serde_json::Value {
+        serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn writes_a_safe_batch() {
+        let dir = export_dir("safe");
+        let profile = EvmProfile {
+            name: "sepolia".to_string(),
+            chain_id: 11155111,
+            rpc_url: String::new(),
+            endpoint: "0x6EDCE65403992e310A62460808c4b910D972f10f".to_string(),
+            eid: 40161,
+        };
+        let safe = evm_client::parse_address("0x00000000000000000000000000000000000000aa").unwrap();
+        let oft = "0x00000000000000000000000000000000000000bb";
+        let mut exporter = Exporter::open(&dir).unwrap();
+        exporter.evm(&profile, safe, oft, vec![0x3c, 0x0c, 0xa5, 0xe1], "setPeer", Vec::new()).unwrap();
+        exporter.evm(&profile, safe, oft, vec![0xf2, 0xfd, 0xe3, 0x8b], "transferOwnership", Vec::new()).unwrap();
+
+        let batch = read_json(dir.join("sepolia.safe.json"));
+        assert_eq!(batch["chainId"], "11155111");
+        assert_eq!(batch["meta"]["createdFromSafeAddress"], format!("{:?}", safe));
+        let transactions = batch["transactions"].as_array().unwrap();
+        assert_eq!(transactions.len(), 2);
+        assert_eq!(transactions[0]["to"], oft);
+        assert_eq!(transactions[0]["value"], "0");
+        assert_eq!(transactions[0]["data"], "0x3c0ca5e1");
+        assert_eq!(transactions[1]["data"], "0xf2fde38b");
+
+        // Another Safe for the same chain would split the batch, so it is refused
+        let other = evm_client::parse_address("0x00000000000000000000000000000000000000cc").unwrap();
+        assert!(exporter.evm(&profile, other, oft, Vec::new(), "setPeer", Vec::new()).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn writes_unsigned_squads_messages_paid_by_the_authority() {
+        let dir = export_dir("squads");
+        let profile = SolanaProfile {
+            rpc_url: String::new(),
+            commitment: CommitmentLevel::Confirmed,
+            endpoint: "76y77prsiCMvXMjuoZ5VRrhG5qYBrUMYTE5WgHqgjEn6".to_string(),
+            eid: 40168,
+            oft_program: crate::network::default_oft_program(),
+        };
+        let (authority, program, store) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let instructions = [Instruction::new_with_bytes(program, &[1, 2, 3], vec![AccountMeta::new(authority, true), AccountMeta::new(store, false)])];
+        let mut exporter = Exporter::open(&dir).unwrap();
+        exporter.solana("solana", &profile, &authority, &instructions, "set_peer_config", Vec::new()).unwrap();
+
+        let batch = read_json(dir.join("solana.squads.json"));
+        let transaction = &batch["transactions"][0];
+        assert_eq!(transaction["authority"], authority.to_string());
+        let from_base58 = bs58::decode(transaction["message_base58"].as_str().unwrap()).into_vec().unwrap();
+        let from_base64 = base64::engine::general_purpose::STANDARD.decode(transaction["message_base64"].as_str().unwrap()).unwrap();
+        assert_eq!(from_base58, from_base64);
+
+        // A bare message: no signatures in front, the header first and the authority as the fee payer
+        let mut expected = Message::new(&instructions, Some(&authority));
+        expected.recent_blockhash = Hash::default();
+        assert_eq!(from_base58, expected.serialize());
+        assert_eq!(from_base58[0], 1, "only the authority signs");
+        assert_eq!(from_base58[3], 3, "authority, store and program keys");
+        assert_eq!(&from_base58[4..36], authority.as_ref());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
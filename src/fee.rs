@@ -0,0 +1,183 @@
+use clap::Subcommand;
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+use anyhow::{Result, Context};
+use crate::deploy::Deployed;
+use crate::evm_ops;
+use crate::export::{self, Check, Exporter, PeerField, StoreField};
+use crate::oft_client::{self, OFTType, OftClient, PeerConfigParam, SetOFTConfigParams, SetPeerConfigParams};
+use crate::target::{ChainArgs, Resolved};
+
+/// `oft-cli fee` subcommands; Solana uses the OFT program's fees, EVM needs an OFT built on `Fee.sol`.
+#[derive(Subcommand)]
+pub enum FeeAction {
+    /// Fee charged on sends to destinations without their own fee
+    SetDefault {
+        #[command(flatten)]
+        chain: ChainArgs,
+        #[arg(long)]
+        bps: u16,
+    },
+    /// Fee for one destination, overriding the default
+    Set {
+        #[command(flatten)]
+        chain: ChainArgs,
+        /// Remote EID, or its chain name in the state file
+        #[arg(long)]
+        peer: String,
+        #[arg(long, required_unless_present = "clear")]
+        bps: Option<u16>,
+        /// Fall back to the default fee for this destination
+        #[arg(long, conflicts_with = "bps")]
+        clear: bool,
+    },
+    /// Withdraw collected fees from the Solana escrow
+    Withdraw {
+        #[command(flatten)]
+        chain: ChainArgs,
+        /// Destination token account of the OFT mint
+        #[arg(long)]
+        dest: Pubkey,
+        /// Amount in local decimals; all collected fees by default
+        #[arg(long)]
+        amount: Option<u64>,
+    },
+    /// Fee settings per destination and, on Solana, collected fees against `tvl_ld`
+    Report {
+        #[command(flatten)]
+        chain: ChainArgs,
+    },
+}
+
+/// Escrow balance, locked value and the fees that can be withdrawn.
+struct EscrowAccounting {
+    balance: u64,
+    tvl_ld: u64,
+}
+
+impl EscrowAccounting {
+    /// `withdraw_fee` keeps `tvl_ld` in the escrow; native OFTs hold no TVL, so it is all fees.
+    fn collected(&self) -> u64 {
+        self.balance.saturating_sub(self.tvl_ld)
+    }
+}
+
+fn escrow_accounting(client: &OftClient, store: &Pubkey) -> Result<(oft_client::OFTStore, EscrowAccounting)> {
+    let oft_store = client.oft_store(store)?;
+    let balance = client.token_balance(&oft_store.token_escrow)?;
+    let tvl_ld = oft_store.tvl_ld;
+    Ok((oft_store, EscrowAccounting { balance, tvl_ld }))
+}
+
+fn fmt_bps(bps: u16) -> String {
+    format!("{} bps ({:.2}%)", bps, bps as f64 / 100.0)
+}
+
+pub async fn run(action: &FeeAction) -> Result<()> {
+    let chain_args = match action {
+        FeeAction::SetDefault { chain, .. } | FeeAction::Set { chain, .. } | FeeAction::Withdraw { chain, .. } | FeeAction::Report { chain } => chain,
+    };
+    let resolved = chain_args.resolve()?;
+    let mut exporter = chain_args.deployment.export.exporter()?;
+    match &resolved.chain.oft {
+        Deployed::Solana(store) => run_solana(action, chain_args, &mut exporter, &resolved, store),
+        Deployed::Evm(addr) => run_evm(action, chain_args, &mut exporter, &resolved, addr).await,
+    }
+}
+
+fn report_solana(client: &OftClient, resolved: &Resolved, store: &Pubkey) -> Result<()> {
+    let (oft_store, escrow) = escrow_accounting(client, store)?;
+    println!("💰 Fees on {} ({})", resolved.chain.name, store);
+    println!("   default fee         {}", fmt_bps(oft_store.default_fee_bps));
+    for peer in &resolved.others {
+        let fee = match client.peer_config(store, peer.eid)? {
+            None => "no peer config".to_string(),
+            Some(config) => config.fee_bps.map_or(format!("default ({})", fmt_bps(oft_store.default_fee_bps)), fmt_bps),
+        };
+        println!("   -> {:<16} {} (EID {})", peer.name, fee, peer.eid);
+    }
+    println!("\n   escrow              {}", oft_store.token_escrow);
+    println!("   escrow balance      {}", escrow.balance);
+    println!("   tvl_ld              {}{}", escrow.tvl_ld, if oft_store.oft_type == OFTType::Native { " (native OFT)" } else { "" });
+    println!("   collected fees      {}", escrow.collected());
+    if escrow.balance < escrow.tvl_ld {
+        println!("⚠️  Escrow holds {} less than tvl_ld: locked tokens are not fully backed", escrow.tvl_ld - escrow.balance);
+    }
+    Ok(())
+}
+
+fn run_solana(action: &FeeAction, chain_args: &ChainArgs, exporter: &mut Option<Exporter>, resolved: &Resolved, store: &Pubkey) -> Result<()> {
+    let client = OftClient::new(&resolved.profile.solana)?;
+    if let FeeAction::Report { .. } = action {
+        return report_solana(&client, resolved, store);
+    }
+    let mut submit = chain_args.deployment.solana_submit(&resolved.chain.name, exporter)?;
+    let admin = submit.solana_authority(|| Ok(client.oft_store(store)?.admin))?;
+    let (instruction, checks): (Instruction, Vec<Check>) = match action {
+        FeeAction::Report { .. } => unreachable!("reported above"),
+        FeeAction::SetDefault { bps, .. } => {
+            println!("💸 Setting Solana default fee to {}...", fmt_bps(*bps));
+            let check = Check::store(store, StoreField::DefaultFeeBps, bps);
+            (client.set_oft_config_ix(&admin, store, SetOFTConfigParams::DefaultFee(*bps))?, vec![check])
+        }
+        FeeAction::Set { peer, bps, .. } => {
+            let eid = resolved.peer_eid(peer)?;
+            client
+                .peer_config(store, eid)?
+                .with_context(|| format!("No peer config for EID {}; set the peer first", eid))?;
+            match bps {
+                Some(bps) => println!("💸 Setting Solana fee for EID {} to {}...", eid, fmt_bps(*bps)),
+                None => println!("💸 Clearing Solana fee for EID {}...", eid),
+            }
+            let check = Check::peer(store, eid, PeerField::FeeBps, export::fmt_fee_bps(*bps));
+            (client.set_peer_config_ix(&admin, store, SetPeerConfigParams { remote_eid: eid, config: PeerConfigParam::FeeBps(*bps) })?, vec![check])
+        }
+        FeeAction::Withdraw { dest, amount, .. } => {
+            let (_, escrow) = escrow_accounting(&client, store)?;
+            let amount = amount.unwrap_or(escrow.collected());
+            if amount == 0 {
+                println!("⏭️  No fees collected");
+                return Ok(());
+            }
+            if amount > escrow.collected() {
+                anyhow::bail!(
+                    "Cannot withdraw {}: only {} of the escrow's {} is fees, the rest backs tvl_ld",
+                    amount, escrow.collected(), escrow.balance
+                );
+            }
+            println!("💸 Withdrawing {} in fees to {}...", amount, dest);
+            // Token balances move on both sides, so there is no state to read back
+            (client.withdraw_fee_ix(&admin, store, dest, amount)?, Vec::new())
+        }
+    };
+    if let Some(sig) = submit.solana(&client, &resolved.chain.name, &admin, &[instruction], "fee", checks)? {
+        println!("✅ Solana fee updated! Tx: {}", sig);
+    }
+    Ok(())
+}
+
+async fn run_evm(action: &FeeAction, chain_args: &ChainArgs, exporter: &mut Option<Exporter>, resolved: &Resolved, addr: &str) -> Result<()> {
+    let profile = resolved.chain.evm.as_ref().expect("EVM chains always carry a profile");
+    match action {
+        FeeAction::Report { .. } => {
+            let default_bps = evm_ops::read_default_fee_bps(profile, addr).await?;
+            println!("💰 Fees on {} ({})", resolved.chain.name, addr);
+            println!("   default fee         {}", fmt_bps(default_bps));
+            for peer in &resolved.others {
+                let fee = match evm_ops::read_fee_bps(profile, addr, peer.eid).await? {
+                    (bps, true) => fmt_bps(bps),
+                    (_, false) => format!("default ({})", fmt_bps(default_bps)),
+                };
+                println!("   -> {:<16} {} (EID {})", peer.name, fee, peer.eid);
+            }
+        }
+        FeeAction::SetDefault { bps, .. } => {
+            evm_ops::set_default_fee_bps_evm(profile, &mut chain_args.deployment.evm_submit(&resolved.chain.name, exporter)?, addr, *bps).await?;
+        }
+        FeeAction::Set { peer, bps, .. } => {
+            let eid = resolved.peer_eid(peer)?;
+            evm_ops::set_fee_bps_evm(profile, &mut chain_args.deployment.evm_submit(&resolved.chain.name, exporter)?, addr, eid, *bps).await?;
+        }
+        FeeAction::Withdraw { .. } => anyhow::bail!("Fee withdrawal is only supported from the Solana escrow"),
+    }
+    Ok(())
+}
@@ -0,0 +1,365 @@
+use borsh::BorshSerialize;
+use flate2::read::ZlibDecoder;
+use serde::Deserialize;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{instruction::AccountMeta, pubkey::Pubkey};
+use std::io::Read;
+use std::path::Path;
+use anyhow::{Result, Context};
+
+/// IDL shipped with the CLI for the `oft-solana` program.
+pub const DEFAULT_IDL_PATH: &str = "solana/oft_v2_idl.json";
+
+/// Anchor IDL (spec 0.1.0, Anchor >= 0.30), reduced to what instruction encoding needs.
+#[derive(Debug, Deserialize)]
+pub struct Idl {
+    #[serde(default)]
+    pub address: Option<String>,
+    pub metadata: IdlMetadata,
+    pub instructions: Vec<IdlInstruction>,
+    #[serde(default)]
+    pub accounts: Vec<IdlAccountDef>,
+    #[serde(default)]
+    pub types: Vec<IdlTypeDef>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct IdlMetadata {
+    pub name: String,
+    pub version: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct IdlInstruction {
+    pub name: String,
+    pub discriminator: Vec<u8>,
+    pub accounts: Vec<IdlAccountItem>,
+    pub args: Vec<IdlField>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum IdlAccountItem {
+    /// Nested `Accounts` struct, flattened in place.
+    Composite { accounts: Vec<IdlAccountItem> },
+    Single {
+        name: String,
+        #[serde(default)]
+        writable: bool,
+        #[serde(default)]
+        signer: bool,
+        #[serde(default)]
+        optional: bool,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+pub struct IdlAccountDef {
+    pub name: String,
+    pub discriminator: Vec<u8>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct IdlField {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub ty: IdlType,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum IdlType {
+    Primitive(String),
+    Option { option: Box<IdlType> },
+    Vec { vec: Box<IdlType> },
+    Array { array: (Box<IdlType>, usize) },
+    Defined { defined: IdlDefined },
+}
+
+/// `{"name": ..}` in spec 0.1.0, a bare string in older IDLs.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum IdlDefined {
+    Named { name: String },
+    Legacy(String),
+}
+
+#[derive(Debug, Deserialize)]
+pub struct IdlTypeDef {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub ty: IdlTypeDefTy,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum IdlTypeDefTy {
+    Struct {
+        #[serde(default)]
+        fields: Option<IdlFields>,
+    },
+    Enum { variants: Vec<IdlVariant> },
+}
+
+#[derive(Debug, Deserialize)]
+pub struct IdlVariant {
+    pub name: String,
+    #[serde(default)]
+    pub fields: Option<IdlFields>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum IdlFields {
+    Named(Vec<IdlField>),
+    Tuple(Vec<IdlType>),
+}
+
+/// Seed Anchor uses for the IDL account, derived with `create_with_seed`.
+const IDL_ACCOUNT_SEED: &str = "anchor:idl";
+/// 8-byte discriminator + authority + u32 length of the zlib-compressed JSON.
+const IDL_ACCOUNT_HEADER: usize = 8 + 32 + 4;
+
+impl Idl {
+    /// Reads an IDL file; `None` if it is missing or empty.
+    pub fn from_file(path: &Path) -> Result<Option<Idl>> {
+        let raw = match std::fs::read_to_string(path) {
+            Ok(raw) => raw,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read IDL {}", path.display())),
+        };
+        if raw.trim().is_empty() {
+            return Ok(None);
+        }
+        let idl = serde_json::from_str(&raw).with_context(|| format!("Invalid Anchor IDL {}", path.display()))?;
+        Ok(Some(idl))
+    }
+
+    /// Address of the on-chain IDL account of `program_id`.
+    pub fn account_address(program_id: &Pubkey) -> Result<Pubkey> {
+        let (base, _) = Pubkey::find_program_address(&[], program_id);
+        Ok(Pubkey::create_with_seed(&base, IDL_ACCOUNT_SEED, program_id)?)
+    }
+
+    /// Fetches and inflates the IDL published with `anchor idl init`, returning the raw JSON.
+    pub fn fetch_json(rpc: &RpcClient, program_id: &Pubkey) -> Result<String> {
+        let address = Self::account_address(program_id)?;
+        let data = rpc
+            .get_account_data(&address)
+            .with_context(|| format!("No on-chain IDL for {} (account {})", program_id, address))?;
+        if data.len() < IDL_ACCOUNT_HEADER {
+            anyhow::bail!("IDL account {} is too short", address);
+        }
+        let len = u32::from_le_bytes(data[40..44].try_into().expect("4 bytes")) as usize;
+        let compressed = data
+            .get(IDL_ACCOUNT_HEADER..IDL_ACCOUNT_HEADER + len)
+            .with_context(|| format!("IDL account {} is truncated", address))?;
+        let mut json = String::new();
+        ZlibDecoder::new(compressed)
+            .read_to_string(&mut json)
+            .with_context(|| format!("Failed to inflate IDL from {}", address))?;
+        Ok(json)
+    }
+
+    /// The IDL at `path`, or the program's on-chain IDL if the file is missing or empty.
+    pub fn load(rpc: &RpcClient, program_id: &Pubkey, path: &Path) -> Result<Idl> {
+        if let Some(idl) = Self::from_file(path)? {
+            // The bundled IDL is built with the example's program ID; redeployments keep its layout
+            if let Some(address) = idl.address.as_deref().filter(|a| *a != program_id.to_string()) {
+                println!("ℹ️  {} was built for {}, using it for {}", path.display(), address, program_id);
+            }
+            return Ok(idl);
+        }
+        println!("📥 {} is empty, fetching the on-chain IDL of {}", path.display(), program_id);
+        let json = Self::fetch_json(rpc, program_id)
+            .with_context(|| format!("No IDL available: fill {} or publish it with `anchor idl init`", path.display()))?;
+        serde_json::from_str(&json).context("Invalid on-chain Anchor IDL")
+    }
+
+    pub fn instruction(&self, name: &str) -> Result<&IdlInstruction> {
+        self.instructions.iter().find(|ix| ix.name == name).with_context(|| {
+            let available: Vec<&str> = self.instructions.iter().map(|ix| ix.name.as_str()).collect();
+            format!(
+                "Instruction '{}' is not in the {} {} IDL. Available: {}",
+                name, self.metadata.name, self.metadata.version, available.join(", ")
+            )
+        })
+    }
+
+    pub fn account_discriminator(&self, name: &str) -> Result<[u8; 8]> {
+        let account = self
+            .accounts
+            .iter()
+            .find(|a| a.name == name)
+            .with_context(|| format!("Account type '{}' is not in the {} IDL", name, self.metadata.name))?;
+        discriminator(&account.discriminator).with_context(|| format!("Bad discriminator for account '{}'", name))
+    }
+
+    /// Checks the accounts and argument types of `name` against the IDL and encodes
+    /// `args` behind the instruction's discriminator.
+    ///
+    /// `accounts` are the instruction's declared accounts, named in order; remaining
+    /// accounts are not part of the IDL and are not passed here. `arg_types` are the
+    /// expected argument names with their type signature (see [`Idl::signature`]).
+    pub fn encode<T: BorshSerialize>(&self, name: &str, accounts: &[(&str, &AccountMeta)], arg_types: &[(&str, &str)], args: &T) -> Result<Vec<u8>> {
+        let ix = self.instruction(name)?;
+
+        let mut expected = Vec::new();
+        flatten_accounts(&ix.accounts, &mut expected);
+        if expected.len() != accounts.len() {
+            let names: Vec<&str> = expected.iter().map(|(n, ..)| *n).collect();
+            anyhow::bail!("'{}' expects {} accounts ({}), got {}", name, expected.len(), names.join(", "), accounts.len());
+        }
+        for (i, ((idl_name, writable, signer, optional), (given, meta))) in expected.iter().zip(accounts).enumerate() {
+            if idl_name != given {
+                anyhow::bail!("'{}' account #{} is '{}' in the IDL, not '{}'", name, i, idl_name, given);
+            }
+            // An absent optional account is passed as the program ID, read-only
+            if *optional && !meta.is_writable && !meta.is_signer {
+                continue;
+            }
+            if *writable && !meta.is_writable {
+                anyhow::bail!("'{}' account '{}' must be writable", name, idl_name);
+            }
+            if *signer && !meta.is_signer {
+                anyhow::bail!("'{}' account '{}' must be a signer", name, idl_name);
+            }
+        }
+
+        if ix.args.len() != arg_types.len() {
+            anyhow::bail!("'{}' takes {} argument(s) in the IDL, got {}", name, ix.args.len(), arg_types.len());
+        }
+        for (arg, (given, sig)) in ix.args.iter().zip(arg_types) {
+            let idl_sig = self.signature(&arg.ty)?;
+            if arg.name != *given || idl_sig != *sig {
+                anyhow::bail!("'{}' argument mismatch: IDL has {}: {}, client encodes {}: {}", name, arg.name, idl_sig, given, sig);
+            }
+        }
+
+        let mut data = discriminator(&ix.discriminator)
+            .with_context(|| format!("Bad discriminator for instruction '{}'", name))?
+            .to_vec();
+        args.serialize(&mut data)?;
+        Ok(data)
+    }
+
+    /// Canonical Borsh layout of `ty`, with defined types expanded, e.g.
+    /// `{remote_eid:u32,config:enum{PeerAddress([u8;32]),FeeBps(option<u16>)}}`.
+    /// `bytes` is written as `vec<u8>`, which it encodes identically to.
+    pub fn signature(&self, ty: &IdlType) -> Result<String> {
+        Ok(match ty {
+            IdlType::Primitive(p) => match p.as_str() {
+                "bytes" => "vec<u8>".to_string(),
+                "publicKey" => "pubkey".to_string(),
+                p => p.to_string(),
+            },
+            IdlType::Option { option } => format!("option<{}>", self.signature(option)?),
+            IdlType::Vec { vec } => format!("vec<{}>", self.signature(vec)?),
+            IdlType::Array { array: (inner, len) } => format!("[{};{}]", self.signature(inner)?, len),
+            IdlType::Defined { defined } => {
+                let name = match defined {
+                    IdlDefined::Named { name } | IdlDefined::Legacy(name) => name,
+                };
+                let def = self
+                    .types
+                    .iter()
+                    .find(|t| &t.name == name)
+                    .with_context(|| format!("Type '{}' is not defined in the IDL", name))?;
+                match &def.ty {
+                    IdlTypeDefTy::Struct { fields } => self.fields_signature(fields.as_ref())?,
+                    IdlTypeDefTy::Enum { variants } => {
+                        let variants = variants
+                            .iter()
+                            .map(|v| Ok(format!("{}{}", v.name, self.fields_signature(v.fields.as_ref())?)))
+                            .collect::<Result<Vec<_>>>()?;
+                        format!("enum{{{}}}", variants.join(","))
+                    }
+                }
+            }
+        })
+    }
+
+    fn fields_signature(&self, fields: Option<&IdlFields>) -> Result<String> {
+        Ok(match fields {
+            None => String::new(),
+            Some(IdlFields::Named(fields)) => {
+                let fields = fields
+                    .iter()
+                    .map(|f| Ok(format!("{}:{}", f.name, self.signature(&f.ty)?)))
+                    .collect::<Result<Vec<_>>>()?;
+                format!("{{{}}}", fields.join(","))
+            }
+            Some(IdlFields::Tuple(types)) => {
+                let types = types.iter().map(|t| self.signature(t)).collect::<Result<Vec<_>>>()?;
+                format!("({})", types.join(","))
+            }
+        })
+    }
+}
+
+/// (name, writable, signer, optional) of every account, composites flattened.
+fn flatten_accounts<'a>(items: &'a [IdlAccountItem], out: &mut Vec<(&'a str, bool, bool, bool)>) {
+    for item in items {
+        match item {
+            IdlAccountItem::Composite { accounts } => flatten_accounts(accounts, out),
+            IdlAccountItem::Single { name, writable, signer, optional } => out.push((name, *writable, *signer, *optional)),
+        }
+    }
+}
+
+fn discriminator(bytes: &[u8]) -> Result<[u8; 8]> {
+    bytes.try_into().map_err(|_| anyhow::anyhow!("expected 8 bytes, got {}", bytes.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::oft_client::SetPauseParams;
+
+    fn idl() -> Idl {
+        Idl::from_file(Path::new(DEFAULT_IDL_PATH)).unwrap().expect("bundled IDL")
+    }
+
+    fn set_pause_accounts() -> (AccountMeta, AccountMeta) {
+        (AccountMeta::new_readonly(Pubkey::new_unique(), true), AccountMeta::new(Pubkey::new_unique(), false))
+    }
+
+    #[test]
+    fn encodes_a_matching_instruction() {
+        let idl = idl();
+        let (signer, store) = set_pause_accounts();
+        let data = idl
+            .encode("set_pause", &[("signer", &signer), ("oft_store", &store)], &[("params", "{paused:bool}")], &SetPauseParams { paused: true })
+            .unwrap();
+        let discriminator = &idl.instruction("set_pause").unwrap().discriminator;
+        assert_eq!(data, [&discriminator[..], &[1]].concat());
+    }
+
+    #[test]
+    fn rejects_a_wrong_argument_type() {
+        let (signer, store) = set_pause_accounts();
+        let err = idl()
+            .encode("set_pause", &[("signer", &signer), ("oft_store", &store)], &[("params", "{paused:u8}")], &1u8)
+            .unwrap_err();
+        assert_eq!(err.to_string(), "'set_pause' argument mismatch: IDL has params: {paused:bool}, client encodes params: {paused:u8}");
+    }
+
+    #[test]
+    fn rejects_reordered_accounts() {
+        let (signer, store) = set_pause_accounts();
+        let err = idl()
+            .encode("set_pause", &[("oft_store", &store), ("signer", &signer)], &[("params", "{paused:bool}")], &SetPauseParams { paused: true })
+            .unwrap_err();
+        assert_eq!(err.to_string(), "'set_pause' account #0 is 'signer' in the IDL, not 'oft_store'");
+    }
+
+    #[test]
+    fn lists_available_instructions_for_an_unknown_name() {
+        let idl = idl();
+        let err = idl.instruction("set_paused").unwrap_err().to_string();
+        assert!(err.starts_with("Instruction 'set_paused' is not in the "), "{}", err);
+        for ix in &idl.instructions {
+            assert!(err.contains(&ix.name), "{} does not list {}", err, ix.name);
+        }
+    }
+}
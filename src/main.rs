@@ -1,10 +1,32 @@
 use clap::{Parser, Subcommand};
-use anyhow::Result;
-use dotenv::dotenv;
-use std::path::Path;
+use anyhow::{Result, Context};
+use std::path::{Path, PathBuf};
 
+mod apply;
+mod deploy;
+mod evm_client;
 mod evm_ops;
+mod export;
+mod fee;
+mod idl;
+mod manifest;
+mod network;
+mod oft_client;
+mod options;
+mod ownership;
+mod pause;
+mod plan;
+mod preflight;
+mod rate_limit;
+mod registry;
+mod send;
+mod signer;
+mod solana_cmd;
 mod solana_ops;
+mod spl;
+mod state;
+mod status;
+mod target;
 mod utils;
 
 #[derive(Parser)]
@@ -18,14 +40,196 @@ struct Cli {
 #[derive(Subcommand)]
 enum Commands {
     Deploy {
+        /// Existing SPL mint backing the Solana OFT
+        #[arg(short, long, required_unless_present = "create_mint")]
+        mint: Option<String>,
+        /// Create a new mint instead of using `--mint`
+        #[arg(long, conflicts_with = "mint")]
+        create_mint: bool,
+        /// Decimals of the created mint
+        #[arg(long, default_value_t = 9, requires = "create_mint")]
+        decimals: u8,
+        /// Create the mint under Token-2022 instead of SPL Token
+        #[arg(long, requires = "create_mint")]
+        token_2022: bool,
+        /// Also create Metaplex metadata for the mint, using `--name`, `--symbol` and this URI
+        #[arg(long, requires = "create_mint")]
+        metadata_uri: Option<String>,
+        /// Mint/burn (native) or lock/unlock (adapter) on Solana; defaults to native
+        /// with `--create-mint` and adapter otherwise
+        #[arg(long, value_enum)]
+        solana_oft_type: Option<oft_client::OFTType>,
+        /// EVM profiles to deploy to within the chosen network; repeat or comma-separate
+        #[arg(short, long, required = true, value_delimiter = ',')]
+        evm_chain_id: Vec<u32>,
+        /// Overrides the EndpointV2 address from the EVM profile (single EVM chain only)
         #[arg(short, long)]
-        mint: String,
-        #[arg(short, long)]
-        evm_chain_id: u32,
+        lz_endpoint: Option<String>,
+        /// Overrides the EID from the EVM profile (single EVM chain only)
+        #[arg(long)]
+        target_eid: Option<u32>,
+        /// Wire only these directed links, FROM:TO by chain name (`solana` or an EVM profile
+        /// name), instead of the full mesh
+        #[arg(long)]
+        link: Vec<String>,
+        #[arg(long, value_enum, default_value = "testnet")]
+        network: network::Network,
+        /// Profile file used with `--network custom`
+        #[arg(long, default_value = "network.toml")]
+        network_file: PathBuf,
+        /// Ignore deployments/<network>.json and redeploy everything
+        #[arg(long)]
+        fresh: bool,
+        /// Compute and simulate every step without broadcasting
+        #[arg(long)]
+        plan: bool,
+        /// ERC20 name of the EVM OFT
+        #[arg(long, default_value = "Synthetix Solana Token")]
+        name: String,
+        /// ERC20 symbol of the EVM OFT
+        #[arg(long, default_value = "SOL-SYN")]
+        symbol: String,
+        /// Owner and endpoint delegate the EVM OFT is handed to after wiring (defaults to the
+        /// deployer)
+        #[arg(long)]
+        delegate: Option<String>,
+        /// Contract on the first EVM chain: a new OFT token, or an adapter over an existing or
+        /// native token. Other EVM chains get plain OFTs
+        #[arg(long, value_enum, default_value = "oft")]
+        evm_oft_type: evm_ops::EvmOftType,
+        /// Existing ERC20 behind `adapter` and `mint-burn-adapter`
+        #[arg(long)]
+        evm_token: Option<String>,
+        /// IMintableBurnable minter of `mint-burn-adapter` (defaults to `--evm-token`)
+        #[arg(long)]
+        minter_burner: Option<String>,
+        /// Decimals of the native gas token for `native-adapter`
+        #[arg(long, default_value_t = evm_ops::default_native_decimals())]
+        native_decimals: u8,
+        #[command(flatten)]
+        signer: signer::SignerArgs,
+    },
+    /// Deploy and wire everything described by a manifest file
+    Apply {
+        /// Path to the TOML or JSON manifest
         #[arg(short, long)]
-        lz_endpoint: String,
+        file: PathBuf,
+        /// Ignore the manifest's state file and redeploy everything
         #[arg(long)]
-        target_eid: u32,
+        fresh: bool,
+        #[command(flatten)]
+        export: export::ExportArgs,
+    },
+    /// Call the OFT program directly for an existing Solana OFT store
+    Solana {
+        /// OFT store PDA
+        #[arg(long)]
+        oft_store: solana_sdk::pubkey::Pubkey,
+        #[arg(long, value_enum, default_value = "testnet")]
+        network: network::Network,
+        /// Profile file used with `--network custom`
+        #[arg(long, default_value = "network.toml")]
+        network_file: PathBuf,
+        #[command(flatten)]
+        signer: signer::SignerArgs,
+        #[command(flatten)]
+        export: export::ExportArgs,
+        #[command(subcommand)]
+        action: solana_cmd::SolanaAction,
+    },
+    /// Read back every peer and report which directions are wired, mismatched or missing
+    Status {
+        /// State file to audit; defaults to deployments/<network>.json unless addresses are given
+        #[arg(long, conflicts_with_all = ["solana_oft", "evm"])]
+        state: Option<PathBuf>,
+        /// Solana OFT store PDA, instead of a state file
+        #[arg(long)]
+        solana_oft: Option<solana_sdk::pubkey::Pubkey>,
+        /// EVM OFT as CHAIN_ID=ADDRESS, instead of a state file; repeatable
+        #[arg(long)]
+        evm: Vec<String>,
+        #[arg(long, value_enum, default_value = "testnet")]
+        network: network::Network,
+        /// Profile file used with `--network custom`
+        #[arg(long, default_value = "network.toml")]
+        network_file: PathBuf,
+        /// Where to write the JSON report; defaults to the state path with `.status.json`
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+    /// Set the executor options one deployed chain enforces on every send to another
+    EnforcedOptions {
+        /// Chain whose OFT enforces the options, as named in the state file
+        #[arg(long)]
+        from: String,
+        /// Remote chain the options pay execution on
+        #[arg(long)]
+        to: String,
+        #[command(flatten)]
+        options: options::EnforcedOptionsSpec,
+        /// State file of the deployment; defaults to deployments/<network>.json
+        #[arg(long)]
+        state: Option<PathBuf>,
+        #[arg(long, value_enum, default_value = "testnet")]
+        network: network::Network,
+        /// Profile file used with `--network custom`
+        #[arg(long, default_value = "network.toml")]
+        network_file: PathBuf,
+        #[command(flatten)]
+        signer: signer::SignerArgs,
+        #[command(flatten)]
+        export: export::ExportArgs,
+    },
+    /// Manage the outbound/inbound rate limiters of a Solana peer
+    RateLimit {
+        #[command(subcommand)]
+        action: rate_limit::RateLimitAction,
+    },
+    /// Set fee bps, withdraw Solana fees, or report fees against the escrow
+    Fee {
+        #[command(subcommand)]
+        action: fee::FeeAction,
+    },
+    /// Pause OFT transfers on Solana and on pausable EVM OFTs; fails if a selected chain is not pausable
+    Pause {
+        #[command(flatten)]
+        targets: pause::PauseTargets,
+    },
+    /// Resume OFT transfers paused with `pause`
+    Unpause {
+        #[command(flatten)]
+        targets: pause::PauseTargets,
+    },
+    /// Assign the Solana OFT store's pauser and unpauser
+    PauseRoles {
+        #[command(flatten)]
+        roles: pause::PauseRoleArgs,
+    },
+    /// Move the OFT's owner/admin and endpoint delegate to a new authority, e.g. a multisig
+    TransferOwnership {
+        #[command(flatten)]
+        args: ownership::TransferOwnershipArgs,
+    },
+    /// Quote and send tokens from one deployed chain to another
+    Send {
+        #[command(flatten)]
+        args: send::SendArgs,
+    },
+    /// Check which actions exported with --export-unsigned have been executed on-chain
+    VerifyExecuted {
+        /// Directory written by --export-unsigned
+        #[arg(long)]
+        dir: PathBuf,
+    },
+    /// Build or decode LayerZero executor/DVN options
+    Options {
+        #[command(subcommand)]
+        action: options::OptionsAction,
+    },
+    /// Query the chain registry (bundled, overridden by a local chains.toml)
+    Chains {
+        #[command(subcommand)]
+        action: registry::ChainsAction,
     },
 }
 
@@ -42,24 +246,147 @@ async fn main() -> Result<()> {
     let cli = Cli::parse();
 
     match &cli.command {
-        Commands::Deploy { mint, evm_chain_id, lz_endpoint, target_eid } => {
-            // 1. Setup Solana Side
-            let sol_oft_pda = solana_ops::init_adapter(mint).await?;
-            
-            // 2. Setup EVM Side
-            let evm_oft_addr = evm_ops::deploy_evm_oft(*evm_chain_id, lz_endpoint).await?;
-            println!("📝 Captured EVM Address: {}", evm_oft_addr);
-
-            // 3. Wire: Solana -> EVM
-            let evm_bytes32 = utils::pad_evm_address(&evm_oft_addr);
-            solana_ops::set_peer_solana(sol_oft_pda, *target_eid, evm_bytes32).await?;
+        Commands::Deploy {
+            mint, create_mint, decimals, token_2022, metadata_uri, solana_oft_type,
+            evm_chain_id, lz_endpoint, target_eid, link, network, network_file, fresh, plan, name, symbol, delegate,
+            evm_oft_type, evm_token, minter_burner, native_decimals, signer,
+        } => {
+            let profile = network::NetworkProfile::load(*network, network_file)?;
+            if evm_chain_id.len() > 1 && (lz_endpoint.is_some() || target_eid.is_some()) {
+                anyhow::bail!("--lz-endpoint and --target-eid only apply to a single --evm-chain-id");
+            }
+            let home_flavour = evm_oft_type.flavour(evm_token.as_ref(), minter_burner.as_ref(), *native_decimals)?;
+            let mut evm = Vec::new();
+            for (i, chain_id) in evm_chain_id.iter().enumerate() {
+                if evm_chain_id[..i].contains(chain_id) {
+                    anyhow::bail!("--evm-chain-id {} is given more than once", chain_id);
+                }
+                let mut evm_profile = profile.evm(*chain_id)?.clone();
+                if let Some(endpoint) = lz_endpoint {
+                    evm_profile.endpoint = endpoint.clone();
+                }
+                if let Some(eid) = target_eid {
+                    evm_profile.eid = *eid;
+                }
+                let flavour = if i == 0 { home_flavour.clone() } else { evm_ops::OftFlavour::Oft };
+                evm.push(deploy::EvmTarget {
+                    profile: evm_profile,
+                    oft: evm_ops::OftParams { name: name.clone(), symbol: symbol.clone(), delegate: delegate.clone(), flavour },
+                });
+            }
+            println!("🌐 Network: {:?} | Solana endpoint {} (EID {})", network, profile.solana.endpoint, profile.solana.eid);
+            for target in &evm {
+                println!("   EVM {} endpoint {} (EID {})", target.profile.name, target.profile.endpoint, target.profile.eid);
+            }
 
-            // 4. Wire: EVM -> Solana
-            let sol_bytes32_hex = utils::pubkey_to_hex32(&sol_oft_pda);
-            evm_ops::set_peer_evm(&evm_oft_addr, *target_eid, sol_bytes32_hex).await?;
-            
-            println!("🚀 Cross-chain setup complete!");
+            let mint = if *create_mint {
+                solana_ops::MintSource::Create(solana_ops::MintSpec {
+                    decimals: *decimals,
+                    token_2022: *token_2022,
+                    metadata: metadata_uri.as_ref().map(|uri| solana_ops::TokenMetadata {
+                        name: name.clone(),
+                        symbol: symbol.clone(),
+                        uri: uri.clone(),
+                    }),
+                })
+            } else {
+                // clap requires --mint without --create-mint
+                solana_ops::MintSource::Existing(mint.clone().expect("--mint is required"))
+            };
+            let mut config = deploy::DeployConfig {
+                network: network.name(),
+                profile,
+                evm,
+                solana_oft: solana_ops::SolanaOftSpec::new(mint, *solana_oft_type),
+                links: Vec::new(),
+                signers: signer.load()?,
+            };
+            let chains = config.chain_names();
+            config.links = if link.is_empty() { deploy::full_mesh(&chains) } else { deploy::parse_links(link, &chains)? };
+            let state_path = state::DeploymentState::path_for(&config.network);
+            let mut state = state::DeploymentState::load(&state_path, *fresh)?;
+            if *plan {
+                let plan_path = state_path.with_extension("plan.json");
+                plan::run(&config, &state, &plan_path).await?;
+            } else {
+                deploy::run(&config, &mut state, &state_path).await?;
+            }
+        }
+        Commands::Apply { file, fresh, export } => {
+            let manifest = manifest::Manifest::load(file)?;
+            let name = file.file_stem().and_then(|s| s.to_str()).unwrap_or("manifest");
+            let state_path = state::DeploymentState::path_for(name);
+            let mut state = state::DeploymentState::load(&state_path, *fresh)?;
+            apply::run(&manifest, &mut state, &state_path, &mut export.exporter()?).await?;
+        }
+        Commands::Solana { oft_store, network, network_file, signer, export, action } => {
+            let profile = network::NetworkProfile::load(*network, network_file)?;
+            let key = signer.load()?.solana(deploy::SOLANA_CHAIN);
+            solana_cmd::run(&profile.solana, &key, oft_store, action, &mut export.exporter()?).await?;
+        }
+        Commands::Status { state, solana_oft, evm, network, network_file, out } => {
+            let profile = network::NetworkProfile::load(*network, network_file)?;
+            let state_path = state.clone().unwrap_or_else(|| state::DeploymentState::path_for(&network.name()));
+            let (chains, recorded) = if solana_oft.is_some() || !evm.is_empty() {
+                let mut chains: Vec<status::AuditChain> = solana_oft.iter().map(|pda| status::AuditChain::solana(*pda, &profile)).collect();
+                for value in evm {
+                    chains.push(status::AuditChain::parse_evm(value, &profile)?);
+                }
+                (chains, None)
+            } else {
+                if !state_path.exists() {
+                    anyhow::bail!("No state file at {}; pass --state or --solana-oft/--evm", state_path.display());
+                }
+                let recorded = state::DeploymentState::load(&state_path, false)?;
+                (status::AuditChain::from_state(&recorded, &profile)?, Some(recorded))
+            };
+            if chains.len() < 2 {
+                anyhow::bail!("Need at least two chains to audit, got {}", chains.len());
+            }
+            let solana_client = if chains.iter().any(|c| c.evm.is_none()) {
+                Some(oft_client::OftClient::new(&profile.solana)?)
+            } else {
+                None
+            };
+            let report = status::audit(&network.name(), solana_client.as_ref(), &chains, recorded.as_ref()).await?;
+            let json_path = out.clone().unwrap_or_else(|| state_path.with_extension("status.json"));
+            status::report(&report, &json_path)?;
+        }
+        Commands::EnforcedOptions { from, to, options, state, network, network_file, signer, export } => {
+            let profile = network::NetworkProfile::load(*network, network_file)?;
+            let state_path = state.clone().unwrap_or_else(|| state::DeploymentState::path_for(&network.name()));
+            let recorded = state::DeploymentState::load(&state_path, false)?;
+            let chains = status::AuditChain::from_state(&recorded, &profile)?;
+            let find = |name: &str| {
+                chains.iter().find(|c| c.name == name).with_context(|| {
+                    let known: Vec<&str> = chains.iter().map(|c| c.name.as_str()).collect();
+                    format!("Chain '{}' is not in {}. Known: {}", name, state_path.display(), known.join(", "))
+                })
+            };
+            let (from_chain, to_chain) = (find(from)?, find(to)?);
+            let mut exporter = export.exporter()?;
+            match (&from_chain.oft, &from_chain.evm) {
+                (deploy::Deployed::Solana(store), _) => {
+                    let mut submit = export::Submit::new(&mut exporter, || Ok(signer.load()?.solana(&from_chain.name)))?;
+                    solana_ops::set_enforced_options_solana(&profile.solana, &mut submit, &from_chain.name, *store, to_chain.eid, options).await?;
+                }
+                (deploy::Deployed::Evm(addr), Some(evm_profile)) => {
+                    let mut submit = export::Submit::new(&mut exporter, || Ok(signer.load()?.evm(&from_chain.name)))?;
+                    evm_ops::set_enforced_options_evm(evm_profile, &mut submit, addr, to_chain.eid, options).await?;
+                }
+                (deploy::Deployed::Evm(_), None) => unreachable!("EVM chains always carry a profile"),
+            }
         }
+        Commands::RateLimit { action } => rate_limit::run(action).await?,
+        Commands::Fee { action } => fee::run(action).await?,
+        Commands::Pause { targets } => pause::run(targets, true).await?,
+        Commands::Unpause { targets } => pause::run(targets, false).await?,
+        Commands::PauseRoles { roles } => pause::set_roles(roles)?,
+        Commands::TransferOwnership { args } => ownership::run(args).await?,
+        Commands::Send { args } => send::run(args).await?,
+        Commands::VerifyExecuted { dir } => export::verify_executed(dir).await?,
+        Commands::Options { action } => options::run(action)?,
+        Commands::Chains { action } => registry::run(action)?,
     }
     Ok(())
 }
\ No newline at end of file
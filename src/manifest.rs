@@ -0,0 +1,221 @@
+use serde::Deserialize;
+use solana_sdk::commitment_config::CommitmentLevel;
+use std::collections::HashSet;
+use std::path::Path;
+use anyhow::{Result, Context};
+use crate::evm_ops::{OftFlavour, OftParams};
+use crate::network::{EvmProfile, SolanaProfile};
+use crate::oft_client::OFTType;
+use crate::options::EnforcedOptionsSpec;
+use crate::rate_limit::PeerRateLimits;
+use crate::signer::KeySource;
+use crate::solana_ops::{MintSource, MintSpec, SolanaOftSpec, TokenMetadata};
+
+/// Declarative description of an OFT deployment, loaded from TOML or JSON.
+#[derive(Debug, Deserialize)]
+pub struct Manifest {
+    pub token: TokenSpec,
+    pub chains: Vec<ChainSpec>,
+    #[serde(default)]
+    pub peers: Vec<PeerSpec>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TokenSpec {
+    /// Existing SPL mint backing the Solana adapter.
+    #[serde(default)]
+    pub mint: Option<String>,
+    /// Create a new mint instead of using `mint`.
+    #[serde(default)]
+    pub create_mint: Option<CreateMintSpec>,
+    /// `native` or `adapter`; native for a created mint, adapter for an existing one by default.
+    #[serde(default)]
+    pub solana_oft_type: Option<OFTType>,
+    /// ERC20 name of the EVM OFTs.
+    pub name: String,
+    /// ERC20 symbol of the EVM OFTs.
+    pub symbol: String,
+    /// Owner and endpoint delegate the EVM OFTs are handed to after wiring; the deployer when
+    /// omitted.
+    #[serde(default)]
+    pub delegate: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateMintSpec {
+    #[serde(default = "default_decimals")]
+    pub decimals: u8,
+    #[serde(default)]
+    pub token_2022: bool,
+    /// Creates Metaplex metadata with the token name and symbol when set.
+    #[serde(default)]
+    pub metadata_uri: Option<String>,
+}
+
+fn default_decimals() -> u8 {
+    9
+}
+
+impl TokenSpec {
+    pub fn oft_params(&self, flavour: &OftFlavour) -> OftParams {
+        OftParams {
+            name: self.name.clone(),
+            symbol: self.symbol.clone(),
+            delegate: self.delegate.clone(),
+            flavour: flavour.clone(),
+        }
+    }
+
+    /// The Solana OFT to deploy; `validate()` ensures exactly one of `mint`/`create_mint` is set.
+    pub fn solana_oft(&self) -> SolanaOftSpec {
+        let mint = match (&self.mint, &self.create_mint) {
+            (Some(mint), _) => MintSource::Existing(mint.clone()),
+            (None, Some(spec)) => MintSource::Create(MintSpec {
+                decimals: spec.decimals,
+                token_2022: spec.token_2022,
+                metadata: spec.metadata_uri.as_ref().map(|uri| TokenMetadata {
+                    name: self.name.clone(),
+                    symbol: self.symbol.clone(),
+                    uri: uri.clone(),
+                }),
+            }),
+            (None, None) => unreachable!("validated token spec"),
+        };
+        SolanaOftSpec::new(mint, self.solana_oft_type)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ChainSpec {
+    pub name: String,
+    #[serde(flatten)]
+    pub kind: ChainKind,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum ChainKind {
+    Evm {
+        chain_id: u32,
+        rpc_url: String,
+        /// EndpointV2 contract address.
+        endpoint: String,
+        eid: u32,
+        deployer_key: KeySource,
+        /// Contract to deploy; a new OFT token by default.
+        #[serde(default)]
+        contract: OftFlavour,
+    },
+    Solana {
+        rpc_url: String,
+        #[serde(default)]
+        commitment: Option<CommitmentLevel>,
+        /// Endpoint program ID.
+        endpoint: String,
+        eid: u32,
+        /// OFT program ID, defaults to the example program's.
+        #[serde(default = "crate::network::default_oft_program")]
+        oft_program: String,
+        deployer_key: KeySource,
+    },
+}
+
+/// A directed edge: `from` gets `to` registered as its peer.
+#[derive(Debug, Deserialize)]
+pub struct PeerSpec {
+    pub from: String,
+    pub to: String,
+    /// Options `from` enforces on every send to `to`.
+    #[serde(default)]
+    pub enforced_options: Option<EnforcedOptionsSpec>,
+    /// Caps on `from`'s limiters for `to`; Solana `from` only. Directions left out are unlimited,
+    /// so omitting it clears any limiters already on-chain.
+    #[serde(default)]
+    pub rate_limit: Option<PeerRateLimits>,
+}
+
+impl ChainSpec {
+    pub fn eid(&self) -> u32 {
+        match &self.kind {
+            ChainKind::Evm { eid, .. } | ChainKind::Solana { eid, .. } => *eid,
+        }
+    }
+
+    pub fn evm_profile(&self) -> Option<EvmProfile> {
+        match &self.kind {
+            ChainKind::Evm { chain_id, rpc_url, endpoint, eid, .. } => Some(EvmProfile {
+                name: self.name.clone(),
+                chain_id: *chain_id,
+                rpc_url: rpc_url.clone(),
+                endpoint: endpoint.clone(),
+                eid: *eid,
+            }),
+            ChainKind::Solana { .. } => None,
+        }
+    }
+
+    pub fn solana_profile(&self) -> Option<SolanaProfile> {
+        match &self.kind {
+            ChainKind::Solana { rpc_url, commitment, endpoint, eid, oft_program, .. } => Some(SolanaProfile {
+                rpc_url: rpc_url.clone(),
+                commitment: commitment.unwrap_or(CommitmentLevel::Confirmed),
+                endpoint: endpoint.clone(),
+                eid: *eid,
+                oft_program: oft_program.clone(),
+            }),
+            ChainKind::Evm { .. } => None,
+        }
+    }
+}
+
+impl Manifest {
+    pub fn load(path: &Path) -> Result<Self> {
+        let raw = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read manifest {}", path.display()))?;
+
+        let manifest: Manifest = match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => serde_json::from_str(&raw).context("Invalid JSON manifest")?,
+            _ => toml::from_str(&raw).context("Invalid TOML manifest")?,
+        };
+
+        manifest.validate()?;
+        Ok(manifest)
+    }
+
+    pub fn chain(&self, name: &str) -> Option<&ChainSpec> {
+        self.chains.iter().find(|c| c.name == name)
+    }
+
+    fn validate(&self) -> Result<()> {
+        let mut names = HashSet::new();
+        for chain in &self.chains {
+            if !names.insert(chain.name.as_str()) {
+                anyhow::bail!("Chain '{}' is declared more than once", chain.name);
+            }
+        }
+
+        let has_solana = self.chains.iter().any(|c| matches!(c.kind, ChainKind::Solana { .. }));
+        match (&self.token.mint, &self.token.create_mint) {
+            (Some(_), Some(_)) => anyhow::bail!("token.mint and token.create_mint are mutually exclusive"),
+            (None, None) if has_solana => anyhow::bail!("Solana chains need token.mint or token.create_mint"),
+            _ => {}
+        }
+
+        for peer in &self.peers {
+            for name in [&peer.from, &peer.to] {
+                if self.chain(name).is_none() {
+                    anyhow::bail!("Peer {} -> {} references unknown chain '{}'", peer.from, peer.to, name);
+                }
+            }
+            if peer.from == peer.to {
+                anyhow::bail!("Chain '{}' cannot be its own peer", peer.from);
+            }
+            let from_solana = matches!(self.chain(&peer.from).map(|c| &c.kind), Some(ChainKind::Solana { .. }));
+            if peer.rate_limit.is_some() && !from_solana {
+                anyhow::bail!("Peer {} -> {}: rate limits are only supported from a Solana chain", peer.from, peer.to);
+            }
+        }
+
+        Ok(())
+    }
+}
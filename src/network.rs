@@ -0,0 +1,146 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use solana_sdk::commitment_config::{CommitmentConfig, CommitmentLevel};
+use std::path::Path;
+use anyhow::{Result, Context};
+use crate::registry::{ChainFamily, Registry, LOCAL_REGISTRY_PATH};
+
+/// Named environment selected with `--network`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Network {
+    Mainnet,
+    Testnet,
+    /// solana-test-validator + anvil, both cloned/forked from testnet
+    Localnet,
+    /// Profile loaded from `--network-file`
+    Custom,
+}
+
+impl Network {
+    /// Lowercase name as accepted by `--network`.
+    pub fn name(self) -> String {
+        self.to_possible_value().expect("no skipped variants").get_name().to_string()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NetworkProfile {
+    pub solana: SolanaProfile,
+    pub evm: Vec<EvmProfile>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SolanaProfile {
+    pub rpc_url: String,
+    #[serde(default = "default_commitment")]
+    pub commitment: CommitmentLevel,
+    /// LayerZero endpoint program ID.
+    pub endpoint: String,
+    pub eid: u32,
+    /// OFT program the Solana side is deployed with.
+    #[serde(default = "default_oft_program")]
+    pub oft_program: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct EvmProfile {
+    pub name: String,
+    pub chain_id: u32,
+    pub rpc_url: String,
+    /// LayerZero EndpointV2 address.
+    pub endpoint: String,
+    pub eid: u32,
+}
+
+fn default_commitment() -> CommitmentLevel {
+    CommitmentLevel::Confirmed
+}
+
+pub fn default_oft_program() -> String {
+    crate::oft_client::DEFAULT_OFT_PROGRAM_ID.to_string()
+}
+
+impl SolanaProfile {
+    pub fn commitment_config(&self) -> CommitmentConfig {
+        CommitmentConfig { commitment: self.commitment }
+    }
+}
+
+impl NetworkProfile {
+    pub fn load(network: Network, custom_file: &Path) -> Result<Self> {
+        let profile = match network {
+            Network::Mainnet | Network::Testnet => registry_profile(&Registry::load(Path::new(LOCAL_REGISTRY_PATH))?, network)?,
+            // Assumes the endpoint program is cloned into the validator and anvil forks Sepolia
+            Network::Localnet => {
+                let registry = Registry::load(Path::new(LOCAL_REGISTRY_PATH))?;
+                let solana = registry.chain("solana-devnet")?;
+                let sepolia = registry.chain("sepolia")?;
+                NetworkProfile {
+                    solana: SolanaProfile {
+                        rpc_url: "http://127.0.0.1:8899".to_string(),
+                        commitment: CommitmentLevel::Confirmed,
+                        endpoint: solana.endpoint.clone(),
+                        eid: solana.eid,
+                        oft_program: solana.oft_program(),
+                    },
+                    evm: vec![EvmProfile {
+                        name: "anvil".to_string(),
+                        chain_id: 31337,
+                        rpc_url: "http://127.0.0.1:8545".to_string(),
+                        endpoint: sepolia.endpoint.clone(),
+                        eid: sepolia.eid,
+                    }],
+                }
+            }
+            Network::Custom => {
+                let raw = std::fs::read_to_string(custom_file)
+                    .with_context(|| format!("Failed to read network profile {}", custom_file.display()))?;
+                toml::from_str(&raw).context("Invalid network profile")?
+            }
+        };
+        Ok(profile)
+    }
+
+    /// Finds the EVM profile for `chain_id`, listing the known ones if it is missing.
+    pub fn evm(&self, chain_id: u32) -> Result<&EvmProfile> {
+        self.evm.iter().find(|p| p.chain_id == chain_id).with_context(|| {
+            let known: Vec<String> = self.evm.iter().map(|p| format!("{} ({})", p.name, p.chain_id)).collect();
+            format!("No EVM profile for chain ID {}. Known: {}", chain_id, known.join(", "))
+        })
+    }
+}
+
+/// Profile for a named network built from its registry entries: one Solana chain and any
+/// number of EVM chains.
+fn registry_profile(registry: &Registry, network: Network) -> Result<NetworkProfile> {
+    let mut solana = None;
+    let mut evm = Vec::new();
+    for chain in registry.on_network(network) {
+        match chain.kind {
+            ChainFamily::Solana => {
+                if solana.is_some() {
+                    anyhow::bail!("Chain registry has more than one Solana chain on {}", network.name());
+                }
+                solana = Some(SolanaProfile {
+                    rpc_url: chain.rpc_url.clone(),
+                    commitment: if network == Network::Mainnet { CommitmentLevel::Finalized } else { CommitmentLevel::Confirmed },
+                    endpoint: chain.endpoint.clone(),
+                    eid: chain.eid,
+                    oft_program: chain.oft_program(),
+                });
+            }
+            ChainFamily::Evm => evm.push(EvmProfile {
+                name: chain.name.clone(),
+                chain_id: chain
+                    .chain_id
+                    .with_context(|| format!("Registry chain '{}' has no chain_id", chain.name))?,
+                rpc_url: chain.rpc_url.clone(),
+                endpoint: chain.endpoint.clone(),
+                eid: chain.eid,
+            }),
+        }
+    }
+    let solana = solana.with_context(|| format!("Chain registry has no Solana chain on {}", network.name()))?;
+    Ok(NetworkProfile { solana, evm })
+}
@@ -0,0 +1,893 @@
+use base64::Engine;
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use solana_client::{rpc_client::RpcClient, rpc_config::{RpcSimulateTransactionConfig, RpcTransactionConfig}};
+use solana_transaction_status::UiTransactionReturnData;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    message::Message,
+    pubkey,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature, Signer},
+    system_program,
+    sysvar::{self, clock::Clock},
+    transaction::Transaction,
+};
+use std::path::Path;
+use std::str::FromStr;
+use anyhow::{Result, Context};
+use crate::network::SolanaProfile;
+use crate::idl::{self, Idl};
+use crate::spl::{self, Mint};
+
+/// Program ID the vendored `oft-solana` example is built with when `OFT_ID` is unset.
+pub const DEFAULT_OFT_PROGRAM_ID: &str = "9UovNrJD8pQyBLheeHNayuG1wJSEAoxkmM14vw5gcsTT";
+
+pub const OFT_SEED: &[u8] = b"OFT";
+pub const PEER_SEED: &[u8] = b"Peer";
+pub const ENFORCED_OPTIONS_SEED: &[u8] = b"EnforcedOptions";
+pub const LZ_RECEIVE_TYPES_SEED: &[u8] = b"LzReceiveTypes";
+
+/// Fee basis points must stay strictly below this.
+pub const MAX_FEE_BASIS_POINTS: u16 = 10_000;
+
+// Endpoint and ULN seeds, from the LayerZero-v2 Solana programs.
+const ENDPOINT_SEED: &[u8] = b"Endpoint";
+const MESSAGE_LIB_SEED: &[u8] = b"MessageLib";
+const SEND_LIBRARY_CONFIG_SEED: &[u8] = b"SendLibraryConfig";
+const NONCE_SEED: &[u8] = b"Nonce";
+const OAPP_SEED: &[u8] = b"OApp";
+const SEND_CONFIG_SEED: &[u8] = b"SendConfig";
+const EVENT_AUTHORITY_SEED: &[u8] = b"__event_authority";
+
+pub const TOKEN_PROGRAM_ID: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+pub const TOKEN_2022_PROGRAM_ID: Pubkey = pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+
+// ============================== Instruction params ==============================
+
+#[derive(BorshSerialize, BorshDeserialize, Deserialize, clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OFTType {
+    /// Mints and burns; the OFT store is the mint authority.
+    Native,
+    /// Locks and unlocks an existing mint in the escrow.
+    Adapter,
+}
+
+#[derive(BorshSerialize, Debug)]
+pub struct InitOFTParams {
+    pub oft_type: OFTType,
+    pub admin: Pubkey,
+    pub shared_decimals: u8,
+    pub endpoint_program: Option<Pubkey>,
+}
+
+#[derive(BorshSerialize, Debug)]
+pub enum SetOFTConfigParams {
+    Admin(Pubkey),
+    /// OApp delegate registered with the endpoint.
+    Delegate(Pubkey),
+    DefaultFee(u16),
+    Paused(bool),
+    Pauser(Option<Pubkey>),
+    Unpauser(Option<Pubkey>),
+}
+
+#[derive(BorshSerialize, Debug)]
+pub struct SetPeerConfigParams {
+    pub remote_eid: u32,
+    pub config: PeerConfigParam,
+}
+
+#[derive(BorshSerialize, Debug)]
+pub enum PeerConfigParam {
+    PeerAddress([u8; 32]),
+    FeeBps(Option<u16>),
+    EnforcedOptions { send: Vec<u8>, send_and_call: Vec<u8> },
+    OutboundRateLimit(Option<RateLimitParams>),
+    InboundRateLimit(Option<RateLimitParams>),
+}
+
+/// `None` fields keep their current value; a `None` limit removes the limiter.
+#[derive(BorshSerialize, Debug)]
+pub struct RateLimitParams {
+    pub refill_per_second: Option<u64>,
+    pub capacity: Option<u64>,
+}
+
+#[derive(BorshSerialize, Debug)]
+pub struct SetPauseParams {
+    pub paused: bool,
+}
+
+#[derive(BorshSerialize, Debug)]
+pub struct WithdrawFeeParams {
+    pub fee_ld: u64,
+}
+
+/// Arguments shared by `quote_oft` and `quote_send`.
+#[derive(BorshSerialize, Clone, Debug)]
+pub struct QuoteParams {
+    pub dst_eid: u32,
+    pub to: [u8; 32],
+    pub amount_ld: u64,
+    pub min_amount_ld: u64,
+    pub options: Vec<u8>,
+    pub compose_msg: Option<Vec<u8>>,
+    pub pay_in_lz_token: bool,
+}
+
+#[derive(BorshSerialize, Clone, Debug)]
+pub struct SendParams {
+    pub dst_eid: u32,
+    pub to: [u8; 32],
+    pub amount_ld: u64,
+    pub min_amount_ld: u64,
+    pub options: Vec<u8>,
+    pub compose_msg: Option<Vec<u8>>,
+    pub native_fee: u64,
+    pub lz_token_fee: u64,
+}
+
+// ============================== Return values ==============================
+
+#[derive(BorshDeserialize, Debug)]
+pub struct QuoteOFTResult {
+    pub oft_limits: OFTLimits,
+    pub oft_fee_details: Vec<OFTFeeDetail>,
+    pub oft_receipt: OFTReceipt,
+}
+
+#[derive(BorshDeserialize, Debug)]
+pub struct OFTLimits {
+    pub min_amount_ld: u64,
+    pub max_amount_ld: u64,
+}
+
+#[derive(BorshDeserialize, Debug)]
+pub struct OFTFeeDetail {
+    pub fee_amount_ld: u64,
+    pub description: String,
+}
+
+#[derive(BorshDeserialize, Debug)]
+pub struct OFTReceipt {
+    pub amount_sent_ld: u64,
+    pub amount_received_ld: u64,
+}
+
+#[derive(BorshDeserialize, Debug)]
+pub struct MessagingFee {
+    pub native_fee: u64,
+    pub lz_token_fee: u64,
+}
+
+#[derive(BorshDeserialize, Debug)]
+pub struct MessagingReceipt {
+    pub guid: [u8; 32],
+    pub nonce: u64,
+    pub fee: MessagingFee,
+}
+
+/// Return value of `send`.
+#[derive(BorshDeserialize, Debug)]
+pub struct SendResult {
+    pub msg_receipt: MessagingReceipt,
+    pub oft_receipt: OFTReceipt,
+}
+
+// ============================== Accounts ==============================
+
+#[derive(BorshDeserialize, Debug)]
+pub struct OFTStore {
+    pub oft_type: OFTType,
+    pub ld2sd_rate: u64,
+    pub token_mint: Pubkey,
+    /// Holds the TVL of adapters and the collected fees.
+    pub token_escrow: Pubkey,
+    pub endpoint_program: Pubkey,
+    pub bump: u8,
+    /// Always 0 for native OFTs.
+    pub tvl_ld: u64,
+    pub admin: Pubkey,
+    pub default_fee_bps: u16,
+    pub paused: bool,
+    pub pauser: Option<Pubkey>,
+    pub unpauser: Option<Pubkey>,
+}
+
+impl OFTStore {
+    pub fn remove_dust(&self, amount_ld: u64) -> u64 {
+        amount_ld - amount_ld % self.ld2sd_rate
+    }
+}
+
+#[derive(BorshDeserialize, Debug)]
+pub struct PeerConfig {
+    pub peer_address: [u8; 32],
+    pub enforced_options: EnforcedOptions,
+    pub outbound_rate_limiter: Option<RateLimiter>,
+    pub inbound_rate_limiter: Option<RateLimiter>,
+    pub fee_bps: Option<u16>,
+    pub bump: u8,
+}
+
+#[derive(BorshDeserialize, Debug)]
+pub struct EnforcedOptions {
+    pub send: Vec<u8>,
+    pub send_and_call: Vec<u8>,
+}
+
+#[derive(BorshDeserialize, Debug, Clone)]
+pub struct RateLimiter {
+    pub capacity: u64,
+    pub tokens: u64,
+    pub refill_per_second: u64,
+    pub last_refill_time: u64,
+}
+
+impl RateLimiter {
+    /// Tokens available at unix time `now`, as `RateLimiter::refill(0)` computes them on-chain.
+    pub fn available(&self, now: u64) -> u64 {
+        let mut new_tokens = 0u64;
+        if now > self.last_refill_time {
+            new_tokens = (now - self.last_refill_time).saturating_mul(self.refill_per_second);
+        }
+        self.capacity.min(self.tokens.saturating_add(new_tokens))
+    }
+}
+
+/// Endpoint `SendLibraryConfig`; the default pubkey means "use the default library".
+#[derive(BorshDeserialize)]
+struct SendLibraryConfig {
+    message_lib: Pubkey,
+}
+
+/// Endpoint `OAppRegistry` of an OApp.
+#[derive(BorshDeserialize)]
+struct OAppRegistry {
+    delegate: Pubkey,
+}
+
+/// Leading fields of the ULN `UlnSettings` account.
+#[derive(BorshDeserialize)]
+struct UlnSettings {
+    _eid: u32,
+    _endpoint: Pubkey,
+    _endpoint_program: Pubkey,
+    _bump: u8,
+    _admin: Pubkey,
+    treasury: Option<UlnTreasury>,
+}
+
+/// Leading fields of the ULN `Treasury`.
+#[derive(BorshDeserialize)]
+struct UlnTreasury {
+    _admin: Option<Pubkey>,
+    native_receiver: Pubkey,
+}
+
+#[derive(BorshDeserialize)]
+struct UlnSendConfig {
+    _bump: u8,
+    uln: UlnConfig,
+    executor: UlnExecutorConfig,
+}
+
+#[derive(BorshDeserialize)]
+struct UlnConfig {
+    _confirmations: u64,
+    required_dvn_count: u8,
+    optional_dvn_count: u8,
+    _optional_dvn_threshold: u8,
+    required_dvns: Vec<Pubkey>,
+    optional_dvns: Vec<Pubkey>,
+}
+
+#[derive(BorshDeserialize)]
+struct UlnExecutorConfig {
+    _max_message_size: u32,
+    executor: Pubkey,
+}
+
+#[derive(BorshDeserialize)]
+struct WorkerAcl {
+    _allow_list: Vec<Pubkey>,
+    _deny_list: Vec<Pubkey>,
+}
+
+/// Leading fields of the executor program's `ExecutorConfig`, up to its price feed.
+#[derive(BorshDeserialize)]
+struct ExecutorWorkerConfig {
+    _bump: u8,
+    _owner: Pubkey,
+    _acl: WorkerAcl,
+    _admins: Vec<Pubkey>,
+    _executors: Vec<Pubkey>,
+    _msglibs: Vec<Pubkey>,
+    _paused: bool,
+    _default_multiplier_bps: u16,
+    price_feed: Pubkey,
+}
+
+/// Leading fields of the DVN program's `DvnConfig`, up to its price feed.
+#[derive(BorshDeserialize)]
+struct DvnWorkerConfig {
+    _vid: u32,
+    _bump: u8,
+    _signers: Vec<[u8; 64]>,
+    _quorum: u8,
+    _acl: WorkerAcl,
+    _paused: bool,
+    _msglibs: Vec<Pubkey>,
+    _admins: Vec<Pubkey>,
+    price_feed: Pubkey,
+}
+
+/// ULN value meaning "explicitly none" in a custom config, as opposed to 0 = "use the default".
+const NIL_DVN_COUNT: u8 = u8::MAX;
+
+// ============================== PDAs ==============================
+
+pub fn oft_store_pda(program_id: &Pubkey, token_escrow: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[OFT_SEED, token_escrow.as_ref()], program_id).0
+}
+
+pub fn peer_pda(program_id: &Pubkey, oft_store: &Pubkey, remote_eid: u32) -> Pubkey {
+    Pubkey::find_program_address(&[PEER_SEED, oft_store.as_ref(), &remote_eid.to_be_bytes()], program_id).0
+}
+
+/// Reserved by the program; enforced options are currently stored in the peer account.
+pub fn enforced_options_pda(program_id: &Pubkey, oft_store: &Pubkey, remote_eid: u32) -> Pubkey {
+    Pubkey::find_program_address(&[ENFORCED_OPTIONS_SEED, oft_store.as_ref(), &remote_eid.to_be_bytes()], program_id).0
+}
+
+pub fn lz_receive_types_pda(program_id: &Pubkey, oft_store: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[LZ_RECEIVE_TYPES_SEED, oft_store.as_ref()], program_id).0
+}
+
+/// Anchor `#[event_cpi]` authority of `program_id`.
+pub fn event_authority_pda(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[EVENT_AUTHORITY_SEED], program_id).0
+}
+
+pub fn oapp_registry_pda(endpoint: &Pubkey, oapp: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[OAPP_SEED, oapp.as_ref()], endpoint).0
+}
+
+fn endpoint_settings_pda(endpoint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[ENDPOINT_SEED], endpoint).0
+}
+
+fn send_library_config_pda(endpoint: &Pubkey, sender: &Pubkey, dst_eid: u32) -> Pubkey {
+    Pubkey::find_program_address(&[SEND_LIBRARY_CONFIG_SEED, sender.as_ref(), &dst_eid.to_be_bytes()], endpoint).0
+}
+
+fn default_send_library_config_pda(endpoint: &Pubkey, dst_eid: u32) -> Pubkey {
+    Pubkey::find_program_address(&[SEND_LIBRARY_CONFIG_SEED, &dst_eid.to_be_bytes()], endpoint).0
+}
+
+fn message_lib_info_pda(endpoint: &Pubkey, message_lib: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[MESSAGE_LIB_SEED, message_lib.as_ref()], endpoint).0
+}
+
+fn nonce_pda(endpoint: &Pubkey, sender: &Pubkey, dst_eid: u32, receiver: &[u8; 32]) -> Pubkey {
+    Pubkey::find_program_address(&[NONCE_SEED, sender.as_ref(), &dst_eid.to_be_bytes(), receiver], endpoint).0
+}
+
+fn uln_send_config_pda(uln_program: &Pubkey, dst_eid: u32, sender: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[SEND_CONFIG_SEED, &dst_eid.to_be_bytes(), sender.as_ref()], uln_program).0
+}
+
+fn uln_default_send_config_pda(uln_program: &Pubkey, dst_eid: u32) -> Pubkey {
+    Pubkey::find_program_address(&[SEND_CONFIG_SEED, &dst_eid.to_be_bytes()], uln_program).0
+}
+
+/// First 8 bytes of sha256("account:<name>"), Anchor's account discriminator.
+fn account_discriminator(name: &str) -> [u8; 8] {
+    let hash = Sha256::digest(format!("account:{}", name));
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(&hash[..8]);
+    discriminator
+}
+
+/// Decodes an Anchor account, checking its discriminator. Trailing bytes are ignored,
+/// so prefix structs can be used for accounts we only need the head of.
+fn decode_account<T: BorshDeserialize>(name: &str, discriminator: [u8; 8], address: &Pubkey, data: &[u8]) -> Result<T> {
+    if data.len() < 8 || data[..8] != discriminator {
+        anyhow::bail!("Account {} is not a {}", address, name);
+    }
+    T::deserialize(&mut &data[8..]).with_context(|| format!("Failed to decode {} at {}", name, address))
+}
+
+/// Decodes an account of the endpoint, ULN or worker programs, which are not in the OFT IDL.
+fn decode_lz_account<T: BorshDeserialize>(name: &str, address: &Pubkey, data: &[u8]) -> Result<T> {
+    decode_account(name, account_discriminator(name), address, data)
+}
+
+// Borsh layouts of the instruction params, in `Idl::signature` form. `encode` checks
+// them against the loaded IDL so a program upgrade cannot silently change the encoding.
+macro_rules! rate_limit_params_sig {
+    () => { "{refill_per_second:option<u64>,capacity:option<u64>}" };
+}
+const INIT_OFT_PARAMS_SIG: &str =
+    "{oft_type:enum{Native,Adapter},admin:pubkey,shared_decimals:u8,endpoint_program:option<pubkey>}";
+const SET_OFT_CONFIG_PARAMS_SIG: &str =
+    "enum{Admin(pubkey),Delegate(pubkey),DefaultFee(u16),Paused(bool),Pauser(option<pubkey>),Unpauser(option<pubkey>)}";
+const SET_PEER_CONFIG_PARAMS_SIG: &str = concat!(
+    "{remote_eid:u32,config:enum{PeerAddress([u8;32]),FeeBps(option<u16>),",
+    "EnforcedOptions{send:vec<u8>,send_and_call:vec<u8>},",
+    "OutboundRateLimit(option<", rate_limit_params_sig!(), ">),",
+    "InboundRateLimit(option<", rate_limit_params_sig!(), ">)}}",
+);
+const SET_PAUSE_PARAMS_SIG: &str = "{paused:bool}";
+const WITHDRAW_FEE_PARAMS_SIG: &str = "{fee_ld:u64}";
+const QUOTE_PARAMS_SIG: &str = concat!(
+    "{dst_eid:u32,to:[u8;32],amount_ld:u64,min_amount_ld:u64,options:vec<u8>,",
+    "compose_msg:option<vec<u8>>,pay_in_lz_token:bool}",
+);
+const SEND_PARAMS_SIG: &str = concat!(
+    "{dst_eid:u32,to:[u8;32],amount_ld:u64,min_amount_ld:u64,options:vec<u8>,",
+    "compose_msg:option<vec<u8>>,native_fee:u64,lz_token_fee:u64}",
+);
+
+// ============================== Client ==============================
+
+/// Typed client for the LayerZero Solana OFT program (`oft-solana` example).
+/// Instructions are checked against and encoded with the program's Anchor IDL.
+pub struct OftClient {
+    pub rpc: RpcClient,
+    pub program_id: Pubkey,
+    pub endpoint: Pubkey,
+    pub idl: Idl,
+    profile: SolanaProfile,
+}
+
+impl OftClient {
+    /// Client using the IDL at `idl::DEFAULT_IDL_PATH`, or the on-chain one if that file is empty.
+    pub fn new(profile: &SolanaProfile) -> Result<Self> {
+        let rpc = RpcClient::new_with_commitment(profile.rpc_url.clone(), profile.commitment_config());
+        let program_id = Pubkey::from_str(&profile.oft_program).context("Invalid OFT program ID")?;
+        let idl = Idl::load(&rpc, &program_id, Path::new(idl::DEFAULT_IDL_PATH))?;
+        Ok(OftClient {
+            rpc,
+            program_id,
+            endpoint: Pubkey::from_str(&profile.endpoint).context("Invalid endpoint program ID")?,
+            idl,
+            profile: profile.clone(),
+        })
+    }
+
+    pub fn profile(&self) -> &SolanaProfile {
+        &self.profile
+    }
+
+    pub fn account_data(&self, address: &Pubkey) -> Result<Option<(Pubkey, Vec<u8>)>> {
+        let account = self.rpc.get_account_with_commitment(address, self.profile.commitment_config())?.value;
+        Ok(account.map(|a| (a.owner, a.data)))
+    }
+
+    /// Cluster time from the `Clock` sysvar, the clock rate limiters refill against.
+    pub fn unix_timestamp(&self) -> Result<u64> {
+        let account = self.rpc.get_account(&sysvar::clock::id())?;
+        let clock: Clock = solana_sdk::account::from_account(&account).context("Invalid Clock sysvar")?;
+        Ok(clock.unix_timestamp.max(0) as u64)
+    }
+
+    fn required_account(&self, address: &Pubkey, what: &str) -> Result<(Pubkey, Vec<u8>)> {
+        self.account_data(address)?
+            .with_context(|| format!("{} {} does not exist", what, address))
+    }
+
+    fn decode_oft_account<T: BorshDeserialize>(&self, name: &str, address: &Pubkey, data: &[u8]) -> Result<T> {
+        decode_account(name, self.idl.account_discriminator(name)?, address, data)
+    }
+
+    pub fn oft_store(&self, oft_store: &Pubkey) -> Result<OFTStore> {
+        let (_, data) = self.required_account(oft_store, "OFT store")?;
+        self.decode_oft_account("OFTStore", oft_store, &data)
+    }
+
+    /// Delegate of the OFT store in the endpoint's OApp registry.
+    pub fn oapp_delegate(&self, oft_store: &Pubkey) -> Result<Pubkey> {
+        let endpoint = self.oft_store(oft_store)?.endpoint_program;
+        let registry = oapp_registry_pda(&endpoint, oft_store);
+        let (_, data) = self.required_account(&registry, "OApp registry")?;
+        Ok(decode_lz_account::<OAppRegistry>("OAppRegistry", &registry, &data)?.delegate)
+    }
+
+    /// Peer config for `remote_eid`, or `None` if no peer has been set.
+    pub fn peer_config(&self, oft_store: &Pubkey, remote_eid: u32) -> Result<Option<PeerConfig>> {
+        let peer = peer_pda(&self.program_id, oft_store, remote_eid);
+        self.account_data(&peer)?
+            .map(|(_, data)| self.decode_oft_account("PeerConfig", &peer, &data))
+            .transpose()
+    }
+
+    /// Token program owning `mint`, SPL Token or Token-2022, with the decoded mint.
+    pub fn mint(&self, mint: &Pubkey) -> Result<(Pubkey, Mint)> {
+        let (owner, data) = self.required_account(mint, "Mint")?;
+        if owner != TOKEN_PROGRAM_ID && owner != TOKEN_2022_PROGRAM_ID {
+            anyhow::bail!("{} is owned by {}, not a token program", mint, owner);
+        }
+        Ok((owner, spl::unpack_mint(mint, &data)?))
+    }
+
+    /// Token program owning `mint`, SPL Token or Token-2022.
+    pub fn token_program_for(&self, mint: &Pubkey) -> Result<Pubkey> {
+        Ok(self.mint(mint)?.0)
+    }
+
+    /// Balance of a token account, in local decimals.
+    pub fn token_balance(&self, token_account: &Pubkey) -> Result<u64> {
+        let (_, data) = self.required_account(token_account, "Token account")?;
+        spl::unpack_token_amount(token_account, &data)
+    }
+
+    /// Validates `accounts` and `params` against the IDL entry for `name`, then builds the
+    /// instruction with `remaining` appended after the declared accounts.
+    fn instruction<T: BorshSerialize>(&self, name: &str, accounts: Vec<(&str, AccountMeta)>, remaining: Vec<AccountMeta>, params_sig: &str, params: &T) -> Result<Instruction> {
+        let named: Vec<(&str, &AccountMeta)> = accounts.iter().map(|(n, m)| (*n, m)).collect();
+        let data = self.idl.encode(name, &named, &[("params", params_sig)], params)?;
+        let mut metas: Vec<AccountMeta> = accounts.into_iter().map(|(_, m)| m).collect();
+        metas.extend(remaining);
+        Ok(Instruction::new_with_bytes(self.program_id, &data, metas))
+    }
+
+    // ------------------------------ Admin ------------------------------
+
+    /// `init_oft`, registering the new OFT store as an OApp with the endpoint.
+    /// `token_escrow` is a fresh keypair that must co-sign; the store PDA is derived from it.
+    pub fn init_oft_ix(&self, payer: &Pubkey, mint: &Pubkey, token_escrow: &Pubkey, token_program: &Pubkey, params: InitOFTParams) -> Result<Instruction> {
+        let endpoint = params.endpoint_program.unwrap_or(self.endpoint);
+        let oft_store = oft_store_pda(&self.program_id, token_escrow);
+        let accounts = vec![
+            ("payer", AccountMeta::new(*payer, true)),
+            ("oft_store", AccountMeta::new(oft_store, false)),
+            ("lz_receive_types_accounts", AccountMeta::new(lz_receive_types_pda(&self.program_id, &oft_store), false)),
+            ("token_mint", AccountMeta::new_readonly(*mint, false)),
+            ("token_escrow", AccountMeta::new(*token_escrow, true)),
+            ("token_program", AccountMeta::new_readonly(*token_program, false)),
+            ("system_program", AccountMeta::new_readonly(system_program::id(), false)),
+        ];
+        // endpoint register_oapp: [program, payer, oapp, oapp_registry, system_program, event_authority, program]
+        let remaining = vec![
+            AccountMeta::new_readonly(endpoint, false),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(oft_store, false),
+            AccountMeta::new(oapp_registry_pda(&endpoint, &oft_store), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(event_authority_pda(&endpoint), false),
+            AccountMeta::new_readonly(endpoint, false),
+        ];
+        self.instruction("init_oft", accounts, remaining, INIT_OFT_PARAMS_SIG, &params)
+    }
+
+    /// `set_oft_config`. `Delegate` also updates the endpoint's OApp registry.
+    pub fn set_oft_config_ix(&self, admin: &Pubkey, oft_store: &Pubkey, params: SetOFTConfigParams) -> Result<Instruction> {
+        if let SetOFTConfigParams::DefaultFee(bps) = params {
+            validate_fee_bps(bps)?;
+        }
+        let accounts = vec![
+            ("admin", AccountMeta::new_readonly(*admin, true)),
+            ("oft_store", AccountMeta::new(*oft_store, false)),
+        ];
+        let mut remaining = Vec::new();
+        if let SetOFTConfigParams::Delegate(_) = params {
+            let endpoint = self.oft_store(oft_store)?.endpoint_program;
+            // endpoint set_delegate: [program, oapp, oapp_registry, event_authority, program]
+            remaining.extend([
+                AccountMeta::new_readonly(endpoint, false),
+                AccountMeta::new_readonly(*oft_store, false),
+                AccountMeta::new(oapp_registry_pda(&endpoint, oft_store), false),
+                AccountMeta::new_readonly(event_authority_pda(&endpoint), false),
+                AccountMeta::new_readonly(endpoint, false),
+            ]);
+        }
+        self.instruction("set_oft_config", accounts, remaining, SET_OFT_CONFIG_PARAMS_SIG, &params)
+    }
+
+    /// `set_peer_config`; the peer account is created on first use, paid by `admin`.
+    pub fn set_peer_config_ix(&self, admin: &Pubkey, oft_store: &Pubkey, params: SetPeerConfigParams) -> Result<Instruction> {
+        if let PeerConfigParam::FeeBps(Some(bps)) = params.config {
+            validate_fee_bps(bps)?;
+        }
+        let accounts = vec![
+            ("admin", AccountMeta::new(*admin, true)),
+            ("peer", AccountMeta::new(peer_pda(&self.program_id, oft_store, params.remote_eid), false)),
+            ("oft_store", AccountMeta::new_readonly(*oft_store, false)),
+            ("system_program", AccountMeta::new_readonly(system_program::id(), false)),
+        ];
+        self.instruction("set_peer_config", accounts, Vec::new(), SET_PEER_CONFIG_PARAMS_SIG, &params)
+    }
+
+    /// `set_pause`, signed by the pauser (to pause) or unpauser (to unpause).
+    pub fn set_pause_ix(&self, signer: &Pubkey, oft_store: &Pubkey, paused: bool) -> Result<Instruction> {
+        let accounts = vec![
+            ("signer", AccountMeta::new_readonly(*signer, true)),
+            ("oft_store", AccountMeta::new(*oft_store, false)),
+        ];
+        self.instruction("set_pause", accounts, Vec::new(), SET_PAUSE_PARAMS_SIG, &SetPauseParams { paused })
+    }
+
+    /// `withdraw_fee` of `fee_ld` from the escrow into `token_dest`.
+    pub fn withdraw_fee_ix(&self, admin: &Pubkey, oft_store: &Pubkey, token_dest: &Pubkey, fee_ld: u64) -> Result<Instruction> {
+        let store = self.oft_store(oft_store)?;
+        let token_program = self.token_program_for(&store.token_mint)?;
+        let accounts = vec![
+            ("admin", AccountMeta::new_readonly(*admin, true)),
+            ("oft_store", AccountMeta::new_readonly(*oft_store, false)),
+            ("token_mint", AccountMeta::new_readonly(store.token_mint, false)),
+            ("token_escrow", AccountMeta::new(store.token_escrow, false)),
+            ("token_dest", AccountMeta::new(*token_dest, false)),
+            ("token_program", AccountMeta::new_readonly(token_program, false)),
+        ];
+        self.instruction("withdraw_fee", accounts, Vec::new(), WITHDRAW_FEE_PARAMS_SIG, &WithdrawFeeParams { fee_ld })
+    }
+
+    // ------------------------------ Public ------------------------------
+
+    fn quote_accounts(&self, store: &OFTStore, oft_store: &Pubkey, dst_eid: u32) -> Vec<(&'static str, AccountMeta)> {
+        vec![
+            ("oft_store", AccountMeta::new_readonly(*oft_store, false)),
+            ("peer", AccountMeta::new_readonly(peer_pda(&self.program_id, oft_store, dst_eid), false)),
+            ("token_mint", AccountMeta::new_readonly(store.token_mint, false)),
+        ]
+    }
+
+    pub fn quote_oft_ix(&self, oft_store: &Pubkey, params: &QuoteParams) -> Result<Instruction> {
+        let store = self.oft_store(oft_store)?;
+        let accounts = self.quote_accounts(&store, oft_store, params.dst_eid);
+        self.instruction("quote_oft", accounts, Vec::new(), QUOTE_PARAMS_SIG, params)
+    }
+
+    pub fn quote_send_ix(&self, oft_store: &Pubkey, params: &QuoteParams) -> Result<Instruction> {
+        let store = self.oft_store(oft_store)?;
+        let accounts = self.quote_accounts(&store, oft_store, params.dst_eid);
+        let remaining = self.endpoint_send_accounts(&store, oft_store, params.dst_eid, None)?;
+        self.instruction("quote_send", accounts, remaining, QUOTE_PARAMS_SIG, params)
+    }
+
+    /// `send` from `signer`'s `token_source`. Only native fee payment is supported.
+    pub fn send_ix(&self, signer: &Pubkey, oft_store: &Pubkey, token_source: &Pubkey, params: &SendParams) -> Result<Instruction> {
+        if params.lz_token_fee > 0 {
+            anyhow::bail!("Paying the messaging fee in LZ token is not supported");
+        }
+        let store = self.oft_store(oft_store)?;
+        let token_program = self.token_program_for(&store.token_mint)?;
+        let accounts = vec![
+            ("signer", AccountMeta::new_readonly(*signer, true)),
+            ("peer", AccountMeta::new(peer_pda(&self.program_id, oft_store, params.dst_eid), false)),
+            ("oft_store", AccountMeta::new(*oft_store, false)),
+            ("token_source", AccountMeta::new(*token_source, false)),
+            ("token_escrow", AccountMeta::new(store.token_escrow, false)),
+            ("token_mint", AccountMeta::new(store.token_mint, false)),
+            ("token_program", AccountMeta::new_readonly(token_program, false)),
+            ("event_authority", AccountMeta::new_readonly(event_authority_pda(&self.program_id), false)),
+            ("program", AccountMeta::new_readonly(self.program_id, false)),
+        ];
+        let remaining = self.endpoint_send_accounts(&store, oft_store, params.dst_eid, Some(signer))?;
+        self.instruction("send", accounts, remaining, SEND_PARAMS_SIG, params)
+    }
+
+    /// Remaining accounts of the endpoint `quote` (no payer) or `send` CPI, followed by the
+    /// send library's: ULN accounts, then executor and DVN worker accounts, 4 each.
+    fn endpoint_send_accounts(&self, store: &OFTStore, sender: &Pubkey, dst_eid: u32, payer: Option<&Pubkey>) -> Result<Vec<AccountMeta>> {
+        let endpoint = store.endpoint_program;
+        let receiver = self
+            .peer_config(sender, dst_eid)?
+            .with_context(|| format!("No peer set for EID {}", dst_eid))?
+            .peer_address;
+
+        let send_library_config = send_library_config_pda(&endpoint, sender, dst_eid);
+        let default_send_library_config = default_send_library_config_pda(&endpoint, dst_eid);
+        let custom: SendLibraryConfig = match self.account_data(&send_library_config)? {
+            Some((_, data)) => decode_lz_account("SendLibraryConfig", &send_library_config, &data)?,
+            None => anyhow::bail!("Send library is not initialized for EID {} (endpoint init_send_library)", dst_eid),
+        };
+        let message_lib = if custom.message_lib == Pubkey::default() {
+            let (_, data) = self.required_account(&default_send_library_config, "Default send library config")?;
+            decode_lz_account::<SendLibraryConfig>("SendLibraryConfig", &default_send_library_config, &data)?.message_lib
+        } else {
+            custom.message_lib
+        };
+        let (uln_program, uln_data) = self.required_account(&message_lib, "Send library")?;
+        let uln: UlnSettings = decode_lz_account("UlnSettings", &message_lib, &uln_data)?;
+
+        let nonce = nonce_pda(&endpoint, sender, dst_eid, &receiver);
+        let mut accounts = vec![AccountMeta::new_readonly(endpoint, false)];
+        if payer.is_some() {
+            accounts.push(AccountMeta::new_readonly(*sender, false));
+        }
+        accounts.extend([
+            AccountMeta::new_readonly(uln_program, false),
+            AccountMeta::new_readonly(send_library_config, false),
+            AccountMeta::new_readonly(default_send_library_config, false),
+            AccountMeta::new_readonly(message_lib_info_pda(&endpoint, &message_lib), false),
+            AccountMeta::new_readonly(endpoint_settings_pda(&endpoint), false),
+            AccountMeta::new(nonce, false),
+        ]);
+        if payer.is_some() {
+            accounts.push(AccountMeta::new_readonly(event_authority_pda(&endpoint), false));
+            accounts.push(AccountMeta::new_readonly(endpoint, false));
+        }
+
+        let send_config = uln_send_config_pda(&uln_program, dst_eid, sender);
+        let default_send_config = uln_default_send_config_pda(&uln_program, dst_eid);
+        accounts.extend([
+            AccountMeta::new_readonly(message_lib, false),
+            AccountMeta::new_readonly(send_config, false),
+            AccountMeta::new_readonly(default_send_config, false),
+        ]);
+        if let Some(payer) = payer {
+            // An absent optional Anchor account is passed as the program ID
+            let treasury = uln.treasury.map(|t| t.native_receiver).unwrap_or(uln_program);
+            accounts.extend([
+                AccountMeta::new(*payer, true),
+                AccountMeta::new(treasury, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(event_authority_pda(&uln_program), false),
+                AccountMeta::new_readonly(uln_program, false),
+            ]);
+        }
+
+        let (_, data) = self.required_account(&default_send_config, "Default ULN send config")?;
+        let default: UlnSendConfig = decode_lz_account("SendConfig", &default_send_config, &data)?;
+        let custom = self
+            .account_data(&send_config)?
+            .map(|(_, data)| decode_lz_account::<UlnSendConfig>("SendConfig", &send_config, &data))
+            .transpose()?;
+        let (executor, dvns) = resolve_workers(default, custom);
+
+        // Worker configs receive fees on send, so they are writable there
+        let writable = payer.is_some();
+        accounts.extend(self.worker_accounts::<ExecutorWorkerConfig>("ExecutorConfig", &executor, writable, |c| c.price_feed)?);
+        for dvn in &dvns {
+            accounts.extend(self.worker_accounts::<DvnWorkerConfig>("DvnConfig", dvn, writable, |c| c.price_feed)?);
+        }
+        Ok(accounts)
+    }
+
+    /// `[worker_program, worker_config, price_feed_program, price_feed_config]` for one worker.
+    fn worker_accounts<T: BorshDeserialize>(&self, name: &str, worker: &Pubkey, writable: bool, price_feed: fn(&T) -> Pubkey) -> Result<Vec<AccountMeta>> {
+        let (worker_program, data) = self.required_account(worker, name)?;
+        let price_feed = price_feed(&decode_lz_account::<T>(name, worker, &data)?);
+        let (price_feed_program, _) = self.required_account(&price_feed, "Price feed")?;
+        let worker_meta = if writable { AccountMeta::new(*worker, false) } else { AccountMeta::new_readonly(*worker, false) };
+        Ok(vec![
+            AccountMeta::new_readonly(worker_program, false),
+            worker_meta,
+            AccountMeta::new_readonly(price_feed_program, false),
+            AccountMeta::new_readonly(price_feed, false),
+        ])
+    }
+
+    // ------------------------------ Execution ------------------------------
+
+    /// Simulates `ix` and decodes the Anchor return value.
+    pub fn simulate_return<T: BorshDeserialize>(&self, payer: &Pubkey, ix: Instruction) -> Result<T> {
+        let transaction = Transaction::new_unsigned(Message::new(&[ix], Some(payer)));
+        let config = RpcSimulateTransactionConfig {
+            sig_verify: false,
+            replace_recent_blockhash: true,
+            commitment: Some(self.profile.commitment_config()),
+            ..Default::default()
+        };
+        let sim = self.rpc.simulate_transaction_with_config(&transaction, config)?.value;
+        if let Some(err) = sim.err {
+            anyhow::bail!("Simulation failed: {} | logs: {}", err, sim.logs.unwrap_or_default().join(" / "));
+        }
+        let return_data = sim.return_data.context("Instruction returned no data")?;
+        let bytes = base64::engine::general_purpose::STANDARD.decode(&return_data.data.0)?;
+        Ok(T::try_from_slice(&bytes)?)
+    }
+
+    /// Decodes what the OFT program returned in a confirmed transaction.
+    pub fn transaction_return<T: BorshDeserialize>(&self, signature: &Signature) -> Result<T> {
+        let config = RpcTransactionConfig {
+            commitment: Some(self.profile.commitment_config()),
+            max_supported_transaction_version: Some(0),
+            ..Default::default()
+        };
+        let transaction = self.rpc.get_transaction_with_config(signature, config)?;
+        let meta = transaction.transaction.meta.with_context(|| format!("Transaction {} has no status meta", signature))?;
+        let return_data: Option<UiTransactionReturnData> = meta.return_data.into();
+        let return_data = return_data.with_context(|| format!("Transaction {} returned no data", signature))?;
+        if return_data.program_id != self.program_id.to_string() {
+            anyhow::bail!("Transaction {} returned data from {}, not the OFT program", signature, return_data.program_id);
+        }
+        let bytes = base64::engine::general_purpose::STANDARD.decode(&return_data.data.0)?;
+        Ok(T::try_from_slice(&bytes)?)
+    }
+
+    pub fn quote_oft(&self, payer: &Pubkey, oft_store: &Pubkey, params: &QuoteParams) -> Result<QuoteOFTResult> {
+        self.simulate_return(payer, self.quote_oft_ix(oft_store, params)?)
+    }
+
+    pub fn quote_send(&self, payer: &Pubkey, oft_store: &Pubkey, params: &QuoteParams) -> Result<MessagingFee> {
+        self.simulate_return(payer, self.quote_send_ix(oft_store, params)?)
+    }
+
+    /// Signs with `payer` plus `extra_signers` and sends; on failure, prints the simulated logs.
+    pub fn send_transaction(&self, instructions: &[Instruction], payer: &dyn Signer, extra_signers: &[&Keypair]) -> Result<Signature> {
+        let mut signers: Vec<&dyn Signer> = vec![payer];
+        signers.extend(extra_signers.iter().map(|signer| *signer as &dyn Signer));
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&payer.pubkey()),
+            &signers,
+            self.rpc.get_latest_blockhash()?,
+        );
+        match self.rpc.send_and_confirm_transaction(&transaction) {
+            Ok(sig) => Ok(sig),
+            Err(e) => {
+                println!("🔍 Simulating transaction to fetch program logs...");
+                if let Ok(sim) = self.rpc.simulate_transaction(&transaction) {
+                    for log in sim.value.logs.unwrap_or_default() {
+                        println!("   {}", log);
+                    }
+                }
+                Err(anyhow::anyhow!("Transaction failed: {}", e))
+            }
+        }
+    }
+}
+
+/// Effective executor and DVN list: custom ULN send config values, falling back to the default.
+fn resolve_workers(default: UlnSendConfig, custom: Option<UlnSendConfig>) -> (Pubkey, Vec<Pubkey>) {
+    let Some(custom) = custom else {
+        let mut dvns = default.uln.required_dvns;
+        dvns.extend(default.uln.optional_dvns);
+        return (default.executor.executor, dvns);
+    };
+    let executor = if custom.executor.executor == Pubkey::default() {
+        default.executor.executor
+    } else {
+        custom.executor.executor
+    };
+    let mut dvns = match custom.uln.required_dvn_count {
+        0 => default.uln.required_dvns,
+        NIL_DVN_COUNT => Vec::new(),
+        _ => custom.uln.required_dvns,
+    };
+    dvns.extend(match custom.uln.optional_dvn_count {
+        0 => default.uln.optional_dvns,
+        NIL_DVN_COUNT => Vec::new(),
+        _ => custom.uln.optional_dvns,
+    });
+    (executor, dvns)
+}
+
+pub fn validate_fee_bps(bps: u16) -> Result<()> {
+    if bps >= MAX_FEE_BASIS_POINTS {
+        anyhow::bail!("Fee of {} bps must be below {}", bps, MAX_FEE_BASIS_POINTS);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(tokens: u64) -> RateLimiter {
+        RateLimiter { capacity: 1_000, tokens, refill_per_second: 10, last_refill_time: 100 }
+    }
+
+    #[test]
+    fn rate_limiter_refills_over_time() {
+        assert_eq!(limiter(200).available(100), 200);
+        assert_eq!(limiter(200).available(130), 500);
+    }
+
+    #[test]
+    fn rate_limiter_caps_at_capacity() {
+        assert_eq!(limiter(200).available(1_000), 1_000);
+        assert_eq!(limiter(990).available(u64::MAX), 1_000);
+    }
+
+    #[test]
+    fn rate_limiter_ignores_clock_before_last_refill() {
+        assert_eq!(limiter(200).available(50), 200);
+    }
+}
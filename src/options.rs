@@ -0,0 +1,470 @@
+use clap::Subcommand;
+use serde::Deserialize;
+use anyhow::Result;
+
+/// Options format understood by `OAppOptionsType3` and the Solana executor.
+pub const TYPE_3: u16 = 3;
+// Legacy formats, still accepted by the executor as send options
+const TYPE_1: u16 = 1;
+const TYPE_2: u16 = 2;
+
+const EXECUTOR_WORKER_ID: u8 = 1;
+const DVN_WORKER_ID: u8 = 2;
+
+// Executor option types
+const OPTION_LZ_RECEIVE: u8 = 1;
+const OPTION_NATIVE_DROP: u8 = 2;
+const OPTION_LZ_COMPOSE: u8 = 3;
+const OPTION_ORDERED_EXECUTION: u8 = 4;
+
+// DVN option types
+const OPTION_PRECRIME: u8 = 1;
+
+/// `OFTMsgCodec` message types keyed by `setEnforcedOptions` on EVM.
+pub const MSG_TYPE_SEND: u16 = 1;
+pub const MSG_TYPE_SEND_AND_CALL: u16 = 2;
+
+/// Executor gas and value the remote chain must be given for every send to it. On Solana,
+/// gas is compute units and value is lamports (e.g. rent for the recipient's token account).
+/// The options encode uint128s, but TOML integers stop at 64 bits.
+#[derive(Clone, Debug, Deserialize, clap::Args)]
+pub struct EnforcedOptionsSpec {
+    /// Gas (compute units on Solana) for `lzReceive` on the remote chain
+    #[arg(long)]
+    pub lz_receive_gas: u64,
+    /// Native value (lamports on Solana) passed to `lzReceive`
+    #[arg(long, default_value_t = 0)]
+    #[serde(default)]
+    pub lz_receive_value: u64,
+    /// Gas for the compose call of a send with a compose message
+    #[arg(long)]
+    #[serde(default)]
+    pub compose_gas: Option<u64>,
+    /// Native value passed to the compose call
+    #[arg(long, default_value_t = 0, requires = "compose_gas")]
+    #[serde(default)]
+    pub compose_value: u64,
+}
+
+impl EnforcedOptionsSpec {
+    /// Options enforced on `send` without a compose message.
+    pub fn send(&self) -> Vec<u8> {
+        let mut options = OptionsBuilder::new();
+        options.lz_receive(self.lz_receive_gas.into(), self.lz_receive_value.into());
+        options.into_bytes()
+    }
+
+    /// Options enforced on `send` with a compose message: `send` plus the compose call at index 0.
+    pub fn send_and_call(&self) -> Vec<u8> {
+        let mut options = OptionsBuilder::new();
+        options.lz_receive(self.lz_receive_gas.into(), self.lz_receive_value.into());
+        if let Some(gas) = self.compose_gas {
+            options.lz_compose(0, gas.into(), self.compose_value.into());
+        }
+        options.into_bytes()
+    }
+}
+
+/// Type-3 options builder, mirroring `OptionsBuilder.sol` from the EVM OApp SDK.
+pub struct OptionsBuilder {
+    bytes: Vec<u8>,
+}
+
+impl Default for OptionsBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OptionsBuilder {
+    pub fn new() -> Self {
+        OptionsBuilder { bytes: TYPE_3.to_be_bytes().to_vec() }
+    }
+
+    /// `addExecutorLzReceiveOption`; the value is omitted when zero.
+    pub fn lz_receive(&mut self, gas: u128, value: u128) -> &mut Self {
+        let mut params = gas.to_be_bytes().to_vec();
+        if value > 0 {
+            params.extend_from_slice(&value.to_be_bytes());
+        }
+        self.executor_option(OPTION_LZ_RECEIVE, &params)
+    }
+
+    /// `addExecutorNativeDropOption`: `amount` of native token to `receiver` on the remote chain.
+    pub fn native_drop(&mut self, amount: u128, receiver: [u8; 32]) -> &mut Self {
+        let mut params = amount.to_be_bytes().to_vec();
+        params.extend_from_slice(&receiver);
+        self.executor_option(OPTION_NATIVE_DROP, &params)
+    }
+
+    /// `addExecutorLzComposeOption` for the compose message at `index`; the value is omitted when zero.
+    pub fn lz_compose(&mut self, index: u16, gas: u128, value: u128) -> &mut Self {
+        let mut params = index.to_be_bytes().to_vec();
+        params.extend_from_slice(&gas.to_be_bytes());
+        if value > 0 {
+            params.extend_from_slice(&value.to_be_bytes());
+        }
+        self.executor_option(OPTION_LZ_COMPOSE, &params)
+    }
+
+    /// `addExecutorOrderedExecutionOption`.
+    pub fn ordered_execution(&mut self) -> &mut Self {
+        self.executor_option(OPTION_ORDERED_EXECUTION, &[])
+    }
+
+    /// `addDVNOption`: raw `option` of `option_type` for the DVN at `dvn_index`.
+    pub fn dvn(&mut self, dvn_index: u8, option_type: u8, option: &[u8]) -> &mut Self {
+        self.bytes.push(DVN_WORKER_ID);
+        self.bytes.extend_from_slice(&(option.len() as u16 + 2).to_be_bytes());
+        self.bytes.push(dvn_index);
+        self.bytes.push(option_type);
+        self.bytes.extend_from_slice(option);
+        self
+    }
+
+    /// worker ID, then the u16 size of option type + params
+    fn executor_option(&mut self, option_type: u8, params: &[u8]) -> &mut Self {
+        self.bytes.push(EXECUTOR_WORKER_ID);
+        self.bytes.extend_from_slice(&(params.len() as u16 + 1).to_be_bytes());
+        self.bytes.push(option_type);
+        self.bytes.extend_from_slice(params);
+        self
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+/// Rejects anything but type-3 options, as `oapp::options::assert_type_3` on Solana and
+/// `_assertOptionsType3` on EVM do.
+pub fn assert_type_3(options: &[u8]) -> Result<()> {
+    if options.len() < 2 || u16::from_be_bytes([options[0], options[1]]) != TYPE_3 {
+        anyhow::bail!("Options 0x{} are not type-3 (must start with 0x0003)", hex::encode(options));
+    }
+    Ok(())
+}
+
+/// Parses user-supplied type-3 options hex, rejecting blobs that do not decode.
+pub fn parse_type_3(value: &str) -> Result<Vec<u8>> {
+    let options = crate::utils::parse_hex(value)?;
+    assert_type_3(&options)?;
+    decode(&options)?;
+    Ok(options)
+}
+
+/// The decoded options on one line, or `none` when empty.
+pub fn describe(options: &[u8]) -> String {
+    if options.is_empty() {
+        return "none".to_string();
+    }
+    match decode(options) {
+        Ok(Options::Type3(parts)) => parts.iter().map(|p| p.to_string()).collect::<Vec<_>>().join("; "),
+        Ok(legacy) => legacy.to_string(),
+        Err(e) => format!("undecodable: {}", e),
+    }
+}
+
+/// Options blob decoded into its parts.
+#[derive(Debug, PartialEq)]
+pub enum Options {
+    /// `0x0001` + uint256 gas.
+    Type1 { gas: u128 },
+    /// `0x0002` + uint256 gas + uint256 native drop amount + receiver.
+    Type2 { gas: u128, native_drop: u128, receiver: Vec<u8> },
+    Type3(Vec<WorkerOption>),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum WorkerOption {
+    LzReceive { gas: u128, value: u128 },
+    NativeDrop { amount: u128, receiver: [u8; 32] },
+    LzCompose { index: u16, gas: u128, value: u128 },
+    OrderedExecution,
+    /// An executor option type this decoder does not know.
+    Executor { option_type: u8, params: Vec<u8> },
+    Dvn { dvn_index: u8, option_type: u8, params: Vec<u8> },
+}
+
+/// Reads a big-endian uint256 that must fit in 128 bits.
+fn read_u256(data: &[u8]) -> Result<u128> {
+    if data[..16].iter().any(|b| *b != 0) {
+        anyhow::bail!("uint256 0x{} does not fit in 128 bits", hex::encode(data));
+    }
+    Ok(u128::from_be_bytes(data[16..32].try_into().expect("16 bytes")))
+}
+
+fn read_u128(data: &[u8]) -> u128 {
+    u128::from_be_bytes(data.try_into().expect("16 bytes"))
+}
+
+/// `gas` followed by an optional `value`, as in lzReceive and lzCompose params.
+fn read_gas_value(params: &[u8], what: &str) -> Result<(u128, u128)> {
+    match params.len() {
+        16 => Ok((read_u128(params), 0)),
+        32 => Ok((read_u128(&params[..16]), read_u128(&params[16..]))),
+        n => anyhow::bail!("Invalid {} option: {} bytes of params", what, n),
+    }
+}
+
+fn decode_executor_option(option_type: u8, params: &[u8]) -> Result<WorkerOption> {
+    Ok(match option_type {
+        OPTION_LZ_RECEIVE => {
+            let (gas, value) = read_gas_value(params, "lzReceive")?;
+            WorkerOption::LzReceive { gas, value }
+        }
+        OPTION_NATIVE_DROP => {
+            if params.len() != 48 {
+                anyhow::bail!("Invalid nativeDrop option: {} bytes of params", params.len());
+            }
+            WorkerOption::NativeDrop { amount: read_u128(&params[..16]), receiver: params[16..].try_into().expect("32 bytes") }
+        }
+        OPTION_LZ_COMPOSE => {
+            if params.len() < 2 {
+                anyhow::bail!("Invalid lzCompose option: {} bytes of params", params.len());
+            }
+            let (gas, value) = read_gas_value(&params[2..], "lzCompose")?;
+            WorkerOption::LzCompose { index: u16::from_be_bytes([params[0], params[1]]), gas, value }
+        }
+        OPTION_ORDERED_EXECUTION if params.is_empty() => WorkerOption::OrderedExecution,
+        _ => WorkerOption::Executor { option_type, params: params.to_vec() },
+    })
+}
+
+/// Decodes type-1, type-2 or type-3 options.
+pub fn decode(options: &[u8]) -> Result<Options> {
+    if options.len() < 2 {
+        anyhow::bail!("Options 0x{} are too short to hold a type", hex::encode(options));
+    }
+    let body = &options[2..];
+    match u16::from_be_bytes([options[0], options[1]]) {
+        TYPE_1 => {
+            if body.len() != 32 {
+                anyhow::bail!("Type-1 options must be 34 bytes, got {}", options.len());
+            }
+            Ok(Options::Type1 { gas: read_u256(body)? })
+        }
+        TYPE_2 => {
+            if body.len() < 64 {
+                anyhow::bail!("Type-2 options must be at least 66 bytes, got {}", options.len());
+            }
+            Ok(Options::Type2 { gas: read_u256(&body[..32])?, native_drop: read_u256(&body[32..64])?, receiver: body[64..].to_vec() })
+        }
+        TYPE_3 => {
+            let mut parsed = Vec::new();
+            let mut rest = body;
+            while !rest.is_empty() {
+                if rest.len() < 4 {
+                    anyhow::bail!("Truncated option header: 0x{}", hex::encode(rest));
+                }
+                let worker_id = rest[0];
+                let size = u16::from_be_bytes([rest[1], rest[2]]) as usize;
+                if size == 0 || rest.len() < 3 + size {
+                    anyhow::bail!("Option of size {} overruns the remaining {} bytes", size, rest.len() - 3);
+                }
+                let option = &rest[3..3 + size];
+                parsed.push(match worker_id {
+                    EXECUTOR_WORKER_ID => decode_executor_option(option[0], &option[1..])?,
+                    DVN_WORKER_ID => {
+                        if option.len() < 2 {
+                            anyhow::bail!("DVN option too short: 0x{}", hex::encode(option));
+                        }
+                        WorkerOption::Dvn { dvn_index: option[0], option_type: option[1], params: option[2..].to_vec() }
+                    }
+                    id => anyhow::bail!("Unknown worker ID {}", id),
+                });
+                rest = &rest[3 + size..];
+            }
+            Ok(Options::Type3(parsed))
+        }
+        other => anyhow::bail!("Unknown options type {}", other),
+    }
+}
+
+impl std::fmt::Display for Options {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Options::Type1 { gas } => write!(f, "type 1: gas {}", gas),
+            Options::Type2 { gas, native_drop, receiver } => {
+                write!(f, "type 2: gas {}, native drop {} to 0x{}", gas, native_drop, hex::encode(receiver))
+            }
+            Options::Type3(parts) => write!(f, "type 3: {} option(s)", parts.len()),
+        }
+    }
+}
+
+impl std::fmt::Display for WorkerOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WorkerOption::LzReceive { gas, value } => write!(f, "executor lzReceive        gas {} value {}", gas, value),
+            WorkerOption::NativeDrop { amount, receiver } => write!(f, "executor nativeDrop       amount {} to 0x{}", amount, hex::encode(receiver)),
+            WorkerOption::LzCompose { index, gas, value } => write!(f, "executor lzCompose        index {} gas {} value {}", index, gas, value),
+            WorkerOption::OrderedExecution => write!(f, "executor orderedExecution"),
+            WorkerOption::Executor { option_type, params } => write!(f, "executor option type {}   params 0x{}", option_type, hex::encode(params)),
+            WorkerOption::Dvn { dvn_index, option_type, params } => {
+                let name = if *option_type == OPTION_PRECRIME { "preCrime".to_string() } else { format!("type {}", option_type) };
+                write!(f, "dvn #{} {:<18} params 0x{}", dvn_index, name, hex::encode(params))
+            }
+        }
+    }
+}
+
+/// `oft-cli options` subcommands.
+#[derive(Subcommand)]
+pub enum OptionsAction {
+    /// Encode type-3 options; flags may repeat and are added in the order below
+    Build {
+        /// GAS[,VALUE]
+        #[arg(long)]
+        lz_receive: Vec<String>,
+        /// AMOUNT,RECEIVER (EVM address, 32-byte hex or base58 pubkey)
+        #[arg(long)]
+        native_drop: Vec<String>,
+        /// INDEX,GAS[,VALUE]
+        #[arg(long)]
+        lz_compose: Vec<String>,
+        #[arg(long)]
+        ordered_execution: bool,
+        /// DVN_INDEX,OPTION_TYPE[,HEX]
+        #[arg(long)]
+        dvn: Vec<String>,
+    },
+    /// Print the parts of an options blob
+    Decode {
+        hex: String,
+    },
+}
+
+fn split_fields<'a>(value: &'a str, flag: &str, min: usize, max: usize) -> Result<Vec<&'a str>> {
+    let fields: Vec<&str> = value.split(',').map(str::trim).collect();
+    if fields.len() < min || fields.len() > max {
+        anyhow::bail!("Invalid --{} '{}'", flag, value);
+    }
+    Ok(fields)
+}
+
+fn parse_field<T: std::str::FromStr>(value: &str, flag: &str) -> Result<T> {
+    value.parse().map_err(|_| anyhow::anyhow!("Invalid number '{}' in --{}", value, flag))
+}
+
+pub fn run(action: &OptionsAction) -> Result<()> {
+    match action {
+        OptionsAction::Build { lz_receive, native_drop, lz_compose, ordered_execution, dvn } => {
+            let mut builder = OptionsBuilder::new();
+            for value in lz_receive {
+                let fields = split_fields(value, "lz-receive", 1, 2)?;
+                let value_wei = fields.get(1).map(|v| parse_field(v, "lz-receive")).transpose()?.unwrap_or(0);
+                builder.lz_receive(parse_field(fields[0], "lz-receive")?, value_wei);
+            }
+            for value in native_drop {
+                let fields = split_fields(value, "native-drop", 2, 2)?;
+                builder.native_drop(parse_field(fields[0], "native-drop")?, crate::utils::parse_bytes32(fields[1])?);
+            }
+            for value in lz_compose {
+                let fields = split_fields(value, "lz-compose", 2, 3)?;
+                let value_wei = fields.get(2).map(|v| parse_field(v, "lz-compose")).transpose()?.unwrap_or(0);
+                builder.lz_compose(parse_field(fields[0], "lz-compose")?, parse_field(fields[1], "lz-compose")?, value_wei);
+            }
+            if *ordered_execution {
+                builder.ordered_execution();
+            }
+            for value in dvn {
+                let fields = split_fields(value, "dvn", 2, 3)?;
+                let option = fields.get(2).map(|v| crate::utils::parse_hex(v)).transpose()?.unwrap_or_default();
+                builder.dvn(parse_field(fields[0], "dvn")?, parse_field(fields[1], "dvn")?, &option);
+            }
+            println!("0x{}", hex::encode(builder.into_bytes()));
+        }
+        OptionsAction::Decode { hex } => {
+            let options = decode(&crate::utils::parse_hex(hex)?)?;
+            println!("{}", options);
+            if let Options::Type3(parts) = &options {
+                for part in parts {
+                    println!("   {}", part);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn built(build: impl FnOnce(&mut OptionsBuilder)) -> String {
+        let mut options = OptionsBuilder::new();
+        build(&mut options);
+        hex::encode(options.into_bytes())
+    }
+
+    // Expected hex is `OptionsBuilder.newOptions()` followed by the matching `add*Option` call.
+
+    #[test]
+    fn encodes_lz_receive_like_solidity() {
+        assert_eq!(built(|o| { o.lz_receive(200_000, 0); }), "00030100110100000000000000000000000000030d40");
+        assert_eq!(
+            built(|o| { o.lz_receive(200_000, 2_039_280); }),
+            "00030100210100000000000000000000000000030d40000000000000000000000000001f1df0"
+        );
+    }
+
+    #[test]
+    fn encodes_native_drop_like_solidity() {
+        let receiver = crate::utils::parse_bytes32("0x000000000000000000000000000000000000dEaD").unwrap();
+        assert_eq!(
+            built(|o| { o.native_drop(1_000_000_000_000_000, receiver); }),
+            "000301003102000000000000000000038d7ea4c68000000000000000000000000000000000000000000000000000000000000000dead"
+        );
+    }
+
+    #[test]
+    fn encodes_lz_compose_like_solidity() {
+        assert_eq!(
+            built(|o| { o.lz_receive(200_000, 0).lz_compose(0, 500_000, 0); }),
+            "00030100110100000000000000000000000000030d400100130300000000000000000000000000000007a120"
+        );
+    }
+
+    #[test]
+    fn decodes_what_it_builds() {
+        let receiver = [0xab; 32];
+        let mut options = OptionsBuilder::new();
+        options
+            .lz_receive(200_000, 5)
+            .native_drop(42, receiver)
+            .lz_compose(1, 500_000, 0)
+            .ordered_execution()
+            .dvn(2, OPTION_PRECRIME, &[0x01, 0x02]);
+        let decoded = decode(&options.into_bytes()).unwrap();
+        assert_eq!(
+            decoded,
+            Options::Type3(vec![
+                WorkerOption::LzReceive { gas: 200_000, value: 5 },
+                WorkerOption::NativeDrop { amount: 42, receiver },
+                WorkerOption::LzCompose { index: 1, gas: 500_000, value: 0 },
+                WorkerOption::OrderedExecution,
+                WorkerOption::Dvn { dvn_index: 2, option_type: OPTION_PRECRIME, params: vec![0x01, 0x02] },
+            ])
+        );
+    }
+
+    #[test]
+    fn enforced_options_add_compose_only_to_send_and_call() {
+        let spec = EnforcedOptionsSpec { lz_receive_gas: 200_000, lz_receive_value: 0, compose_gas: Some(500_000), compose_value: 0 };
+        assert_eq!(decode(&spec.send()).unwrap(), Options::Type3(vec![WorkerOption::LzReceive { gas: 200_000, value: 0 }]));
+        assert_eq!(
+            decode(&spec.send_and_call()).unwrap(),
+            Options::Type3(vec![
+                WorkerOption::LzReceive { gas: 200_000, value: 0 },
+                WorkerOption::LzCompose { index: 0, gas: 500_000, value: 0 },
+            ])
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_options() {
+        assert!(decode(&hex::decode("000301001101").unwrap()).is_err());
+        assert!(parse_type_3("0x0001").is_err());
+    }
+}
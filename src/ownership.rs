@@ -0,0 +1,89 @@
+use std::str::FromStr;
+use solana_sdk::pubkey::Pubkey;
+use anyhow::{Result, Context};
+use crate::deploy::Deployed;
+use crate::export::{Check, StoreField, Submit};
+use crate::oft_client::{OftClient, SetOFTConfigParams};
+use crate::target::ChainArgs;
+use crate::{evm_client, evm_ops};
+
+/// New authority for one chain of a deployment.
+#[derive(clap::Args)]
+pub struct TransferOwnershipArgs {
+    #[command(flatten)]
+    pub chain: ChainArgs,
+    /// New owner/admin and delegate: an EVM address or a Solana pubkey, e.g. a multisig
+    #[arg(long)]
+    pub to: String,
+    /// Transfer even if the new authority has no code (EVM) or no account (Solana)
+    #[arg(long)]
+    pub force: bool,
+}
+
+/// Moves `admin` and the endpoint delegate of the OFT store to `new_admin` in one tx,
+/// delegate first since only the admin may change it, then reads both back unless exported.
+fn transfer_solana(client: &OftClient, mut submit: Submit<'_>, chain: &str, store: &Pubkey, new_admin: Pubkey, force: bool) -> Result<()> {
+    let oft_store = client.oft_store(store)?;
+    let delegate = client.oapp_delegate(store)?;
+    println!("   admin               {}", oft_store.admin);
+    println!("   delegate            {}", delegate);
+    if oft_store.admin == new_admin && delegate == new_admin {
+        println!("⏭️  {} is already administered by {}", store, new_admin);
+        return Ok(());
+    }
+    if new_admin == Pubkey::default() {
+        anyhow::bail!("Refusing to transfer the OFT store to the default pubkey");
+    }
+    let admin = submit.solana_authority(|| Ok(oft_store.admin))?;
+    if oft_store.admin != admin {
+        anyhow::bail!("Signer {} is not the admin ({}) of {}", admin, oft_store.admin, store);
+    }
+    if client.account_data(&new_admin)?.is_none() && !force {
+        anyhow::bail!("{} has no account on the cluster; pass --force to transfer to it anyway", new_admin);
+    }
+
+    let mut instructions = Vec::new();
+    if delegate != new_admin {
+        println!("🔑 Setting delegate of {} to {}...", store, new_admin);
+        instructions.push(client.set_oft_config_ix(&admin, store, SetOFTConfigParams::Delegate(new_admin))?);
+    }
+    if oft_store.admin != new_admin {
+        println!("🔑 Transferring admin of {} to {}...", store, new_admin);
+        instructions.push(client.set_oft_config_ix(&admin, store, SetOFTConfigParams::Admin(new_admin))?);
+    }
+    let checks = vec![
+        Check::store(store, StoreField::Admin, new_admin),
+        Check::store(store, StoreField::Delegate, new_admin),
+    ];
+    match submit.solana(client, chain, &admin, &instructions, "set_oft_config", checks)? {
+        Some(sig) => println!("✅ Tx: {}", sig),
+        None => return Ok(()),
+    }
+
+    let admin = client.oft_store(store)?.admin;
+    let delegate = client.oapp_delegate(store)?;
+    if admin != new_admin || delegate != new_admin {
+        anyhow::bail!("Read-back mismatch on {}: admin {}, delegate {}, expected {}", store, admin, delegate, new_admin);
+    }
+    println!("   ✔ admin = delegate = {}", new_admin);
+    Ok(())
+}
+
+pub async fn run(args: &TransferOwnershipArgs) -> Result<()> {
+    let resolved = args.chain.resolve()?;
+    let mut exporter = args.chain.deployment.export.exporter()?;
+    println!("👑 Transferring ownership of {} to {}...", resolved.chain.name, args.to);
+    match &resolved.chain.oft {
+        Deployed::Solana(store) => {
+            let new_admin = Pubkey::from_str(&args.to).with_context(|| format!("Invalid Solana pubkey '{}'", args.to))?;
+            let client = OftClient::new(&resolved.profile.solana)?;
+            transfer_solana(&client, args.chain.deployment.solana_submit(&resolved.chain.name, &mut exporter)?, &resolved.chain.name, store, new_admin, args.force)
+        }
+        Deployed::Evm(addr) => {
+            let profile = resolved.chain.evm.as_ref().expect("EVM chains always carry a profile");
+            let new_owner = evm_client::parse_address(&args.to)?;
+            let mut submit = args.chain.deployment.evm_submit(&resolved.chain.name, &mut exporter)?;
+            evm_ops::transfer_ownership_evm(profile, &mut submit, addr, new_owner, args.force).await
+        }
+    }
+}
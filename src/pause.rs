@@ -0,0 +1,210 @@
+use std::str::FromStr;
+use solana_sdk::pubkey::Pubkey;
+use anyhow::{Result, Context};
+use crate::deploy::Deployed;
+use crate::evm_ops;
+use crate::export::{self, Check, Exporter, StoreField, Submit};
+use crate::network::NetworkProfile;
+use crate::oft_client::{OftClient, SetOFTConfigParams};
+use crate::status::AuditChain;
+use crate::target::{ChainArgs, DeploymentArgs};
+
+/// Chains of a deployment to pause or unpause.
+#[derive(clap::Args)]
+pub struct PauseTargets {
+    #[command(flatten)]
+    pub deployment: DeploymentArgs,
+    /// Every chain in the state file; failures on one chain do not stop the others
+    #[arg(long, conflicts_with = "chain")]
+    pub all: bool,
+    /// Chains by name in the state file; repeat or comma-separate
+    #[arg(long, value_delimiter = ',', required_unless_present = "all")]
+    pub chain: Vec<String>,
+}
+
+/// Pauser and unpauser of a Solana OFT store, set by its admin.
+#[derive(clap::Args)]
+pub struct PauseRoleArgs {
+    #[command(flatten)]
+    pub chain: ChainArgs,
+    /// New pauser, or `none` to remove the role
+    #[arg(long, required_unless_present = "unpauser")]
+    pub pauser: Option<String>,
+    /// New unpauser, or `none` to remove the role
+    #[arg(long)]
+    pub unpauser: Option<String>,
+}
+
+enum Outcome {
+    Changed(String),
+    Exported,
+    Unchanged,
+    Unsupported,
+    Failed(anyhow::Error),
+}
+
+fn state_word(paused: bool) -> &'static str {
+    if paused { "paused" } else { "unpaused" }
+}
+
+/// `set_pause` when the signer holds the pauser (or unpauser) role, else the admin's `Paused` config.
+/// Exports go to the role holder if there is one, else to the admin.
+fn set_paused_solana(client: &OftClient, mut submit: Submit<'_>, chain: &str, store: &Pubkey, paused: bool) -> Result<Outcome> {
+    let oft_store = client.oft_store(store)?;
+    if oft_store.paused == paused {
+        return Ok(Outcome::Unchanged);
+    }
+    let (role_name, role) = if paused { ("pauser", oft_store.pauser) } else { ("unpauser", oft_store.unpauser) };
+    let me = submit.solana_authority(|| Ok(role.unwrap_or(oft_store.admin)))?;
+    let ix = if role == Some(me) {
+        client.set_pause_ix(&me, store, paused)?
+    } else if oft_store.admin == me {
+        client.set_oft_config_ix(&me, store, SetOFTConfigParams::Paused(paused))?
+    } else {
+        anyhow::bail!("{} is neither the {} ({}) nor the admin ({})", me, role_name, role.map_or("unset".to_string(), |r| r.to_string()), oft_store.admin);
+    };
+    let check = Check::store(store, StoreField::Paused, paused);
+    Ok(match submit.solana(client, chain, &me, &[ix], "pause", vec![check])? {
+        Some(sig) => Outcome::Changed(sig.to_string()),
+        None => Outcome::Exported,
+    })
+}
+
+async fn set_paused_evm(mut submit: Submit<'_>, chain: &AuditChain, addr: &str, paused: bool) -> Result<Outcome> {
+    let profile = chain.evm.as_ref().expect("EVM chains always carry a profile");
+    match evm_ops::read_paused(profile, addr).await? {
+        None => Ok(Outcome::Unsupported),
+        Some(current) if current == paused => Ok(Outcome::Unchanged),
+        Some(_) => Ok(match evm_ops::set_paused_evm(profile, &mut submit, addr, paused).await? {
+            Some(tx) => Outcome::Changed(tx),
+            None => Outcome::Exported,
+        }),
+    }
+}
+
+async fn set_paused(profile: &NetworkProfile, deployment: &DeploymentArgs, exporter: &mut Option<Exporter>, chain: &AuditChain, paused: bool) -> Result<Outcome> {
+    match &chain.oft {
+        Deployed::Solana(store) => {
+            let client = OftClient::new(&profile.solana)?;
+            set_paused_solana(&client, deployment.solana_submit(&chain.name, exporter)?, &chain.name, store, paused)
+        }
+        Deployed::Evm(addr) => set_paused_evm(deployment.evm_submit(&chain.name, exporter)?, chain, addr, paused).await,
+    }
+}
+
+/// Pauses (or unpauses) the selected chains one by one and prints a result per chain.
+pub async fn run(targets: &PauseTargets, paused: bool) -> Result<()> {
+    let (profile, chains) = targets.deployment.load()?;
+    let selected: Vec<&AuditChain> = if targets.all {
+        chains.iter().collect()
+    } else {
+        let mut selected = Vec::new();
+        for name in &targets.chain {
+            let chain = chains
+                .iter()
+                .find(|c| &c.name == name)
+                .with_context(|| format!("Chain '{}' is not in {}", name, targets.deployment.state_path().display()))?;
+            selected.push(chain);
+        }
+        selected
+    };
+
+    let verb = if paused { "Pausing" } else { "Unpausing" };
+    println!("🛑 {} {} chain(s) on {}...", verb, selected.len(), targets.deployment.network.name());
+    let mut exporter = targets.deployment.export.exporter()?;
+    let mut results = Vec::new();
+    for chain in selected {
+        let outcome = set_paused(&profile, &targets.deployment, &mut exporter, chain, paused).await.unwrap_or_else(Outcome::Failed);
+        results.push((chain, outcome));
+    }
+
+    println!("\n   {:<16} {:<8} RESULT", "CHAIN", "EID");
+    let (mut done, mut unsupported, mut failed) = (0, 0, 0);
+    for (chain, outcome) in &results {
+        let result = match outcome {
+            Outcome::Changed(tx) => {
+                done += 1;
+                format!("✅ {} (tx {})", state_word(paused), tx)
+            }
+            Outcome::Exported => {
+                done += 1;
+                "📝 exported unsigned".to_string()
+            }
+            Outcome::Unchanged => {
+                done += 1;
+                format!("⏭️  already {}", state_word(paused))
+            }
+            Outcome::Unsupported => {
+                unsupported += 1;
+                "➖ not pausable".to_string()
+            }
+            Outcome::Failed(e) => {
+                failed += 1;
+                format!("❌ {:#}", e)
+            }
+        };
+        println!("   {:<16} {:<8} {}", chain.name, chain.eid, result);
+    }
+
+    println!("\n   {} {} or exported, {} not pausable, {} failed", done, state_word(paused), unsupported, failed);
+    if unsupported + failed > 0 {
+        anyhow::bail!("{} of {} chain(s) could not be {}", unsupported + failed, results.len(), state_word(paused));
+    }
+    Ok(())
+}
+
+fn parse_role(value: &str) -> Result<Option<Pubkey>> {
+    if value.eq_ignore_ascii_case("none") {
+        return Ok(None);
+    }
+    Ok(Some(Pubkey::from_str(value).with_context(|| format!("Invalid pubkey '{}'", value))?))
+}
+
+fn fmt_role(role: Option<Pubkey>) -> String {
+    role.map_or("none".to_string(), |r| r.to_string())
+}
+
+pub fn set_roles(args: &PauseRoleArgs) -> Result<()> {
+    let resolved = args.chain.resolve()?;
+    let Deployed::Solana(store) = resolved.chain.oft else {
+        anyhow::bail!("Pause roles are only managed on Solana; grant roles on '{}' through the contract's own access control", args.chain.chain);
+    };
+    let client = OftClient::new(&resolved.profile.solana)?;
+    let oft_store = client.oft_store(&store)?;
+    let mut exporter = args.chain.deployment.export.exporter()?;
+    let mut submit = args.chain.deployment.solana_submit(&resolved.chain.name, &mut exporter)?;
+    let admin = submit.solana_authority(|| Ok(oft_store.admin))?;
+
+    let mut instructions = Vec::new();
+    let mut checks = Vec::new();
+    if let Some(pauser) = &args.pauser {
+        let pauser = parse_role(pauser)?;
+        if pauser == oft_store.pauser {
+            println!("⏭️  Pauser is already {}", fmt_role(pauser));
+        } else {
+            println!("🔑 Pauser: {} -> {}", fmt_role(oft_store.pauser), fmt_role(pauser));
+            instructions.push(client.set_oft_config_ix(&admin, &store, SetOFTConfigParams::Pauser(pauser))?);
+            checks.push(Check::store(&store, StoreField::Pauser, export::fmt_key(pauser)));
+        }
+    }
+    if let Some(unpauser) = &args.unpauser {
+        let unpauser = parse_role(unpauser)?;
+        if unpauser == oft_store.unpauser {
+            println!("⏭️  Unpauser is already {}", fmt_role(unpauser));
+        } else {
+            println!("🔑 Unpauser: {} -> {}", fmt_role(oft_store.unpauser), fmt_role(unpauser));
+            instructions.push(client.set_oft_config_ix(&admin, &store, SetOFTConfigParams::Unpauser(unpauser))?);
+            checks.push(Check::store(&store, StoreField::Unpauser, export::fmt_key(unpauser)));
+        }
+    }
+    if instructions.is_empty() {
+        return Ok(());
+    }
+    if oft_store.admin != admin {
+        anyhow::bail!("{} is not the OFT store admin ({})", admin, oft_store.admin);
+    }
+    if let Some(sig) = submit.solana(&client, &resolved.chain.name, &admin, &instructions, "set_oft_config", checks)? {
+        println!("✅ Pause roles updated! Tx: {}", sig);
+    }
+    Ok(())
+}
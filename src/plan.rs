@@ -0,0 +1,273 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+use std::str::FromStr;
+use solana_sdk::{pubkey::Pubkey, signature::{Keypair, Signer}};
+use anyhow::Result;
+use crate::deploy::{self, DeployConfig, Deployed, SOLANA_CHAIN};
+use crate::network::NetworkProfile;
+use crate::oft_client::OftClient;
+use crate::solana_ops::MintSource;
+use crate::state::DeploymentState;
+use crate::{evm_client, evm_ops, solana_ops};
+
+/// Everything the deploy flow would do, computed without broadcasting.
+#[derive(Serialize)]
+pub struct Plan {
+    pub network: String,
+    pub steps: Vec<PlanStep>,
+    pub diff: Vec<ResourceDiff>,
+}
+
+#[derive(Serialize)]
+pub struct PlanStep {
+    pub step: usize,
+    pub chain: String,
+    pub action: String,
+    pub skip: bool,
+    pub details: BTreeMap<String, String>,
+}
+
+/// Current vs. desired value of one piece of on-chain state.
+#[derive(Serialize)]
+pub struct ResourceDiff {
+    pub resource: String,
+    pub current: Option<String>,
+    pub desired: String,
+    pub change: Change,
+}
+
+#[derive(Serialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Change {
+    Create,
+    Update,
+    None,
+}
+
+impl ResourceDiff {
+    fn new(resource: String, current: Option<String>, desired: String) -> Self {
+        let change = match &current {
+            None => Change::Create,
+            Some(c) if c.eq_ignore_ascii_case(&desired) => Change::None,
+            Some(_) => Change::Update,
+        };
+        ResourceDiff { resource, current, desired, change }
+    }
+}
+
+/// Builds the plan for the deploy flow and writes its JSON next to the state file.
+pub async fn run(config: &DeployConfig, state: &DeploymentState, plan_path: &Path) -> Result<()> {
+    let DeployConfig { network, profile, solana_oft, signers, .. } = config;
+    let payer = signers.solana(SOLANA_CHAIN).solana_signer()?.pubkey();
+    deploy::check_decimals(config, state).await?;
+    let mut steps = Vec::new();
+    let mut diff = Vec::new();
+
+    // 1. Solana OFT
+    let mut details = BTreeMap::new();
+    let recorded_pda = solana_oft.recorded_pda(state, SOLANA_CHAIN)?;
+    let (sol_oft_pda, adapter_exists) = match &recorded_pda {
+        Some(pda) => {
+            let pda = Pubkey::from_str(pda)?;
+            (pda, solana_ops::account_exists(&profile.solana, &pda)?)
+        }
+        None => {
+            // The store PDA is derived from the escrow keypair, which deploy generates afresh
+            let token_escrow = Keypair::new();
+            details.insert("escrow".to_string(), format!("{} (example; a new keypair is generated on deploy)", token_escrow.pubkey()));
+            let new_mint = Keypair::new();
+            let mint = match &solana_oft.mint {
+                MintSource::Existing(mint) => Pubkey::from_str(mint)?,
+                MintSource::Create(_) => {
+                    details.insert("mint".to_string(), format!("{} (example; a new keypair is generated on deploy)", new_mint.pubkey()));
+                    new_mint.pubkey()
+                }
+            };
+            let (pda, instructions) = solana_ops::build_init_oft_ixs(&profile.solana, &payer, solana_oft, &mint, &token_escrow.pubkey())?;
+            let init_ix = instructions.last().expect("init_oft is always built");
+            details.insert("program".to_string(), init_ix.program_id.to_string());
+            details.insert("data".to_string(), hex::encode(&init_ix.data));
+            details.insert("accounts".to_string(), format_accounts(init_ix));
+            details.insert("simulation".to_string(), simulate(profile, &payer, &instructions)?);
+            (pda, false)
+        }
+    };
+    details.insert("oft_store".to_string(), sol_oft_pda.to_string());
+    // Deploy trusts the state, so a recorded store that is gone stays skipped
+    if recorded_pda.is_some() && !adapter_exists {
+        details.insert("warning".to_string(), "recorded in the state but missing on-chain; deploy will skip it, rerun with --fresh".to_string());
+    }
+    steps.push(PlanStep {
+        step: 1,
+        chain: SOLANA_CHAIN.to_string(),
+        action: solana_oft.action(),
+        skip: recorded_pda.is_some(),
+        details,
+    });
+    diff.push(ResourceDiff::new(
+        format!("{}.oft_store", SOLANA_CHAIN),
+        adapter_exists.then(|| sol_oft_pda.to_string()),
+        sol_oft_pda.to_string(),
+    ));
+
+    let mut deployed: HashMap<String, Deployed> = HashMap::new();
+    deployed.insert(SOLANA_CHAIN.to_string(), Deployed::Solana(sol_oft_pda));
+    // EVM OFTs that exist on-chain, so their peers can be read back
+    let mut evm_existing = HashSet::new();
+
+    // 2. EVM OFTs
+    for target in &config.evm {
+        let evm_profile = &target.profile;
+        let recorded_addr = state.evm_oft(&evm_profile.name, &target.oft.spec())?;
+        let evm_oft_addr = match &recorded_addr {
+            Some(addr) => {
+                evm_existing.insert(evm_profile.name.clone());
+                addr.clone()
+            }
+            None => evm_ops::simulate_deploy_evm_oft(evm_profile, &signers.evm(&evm_profile.name), &target.oft).await?,
+        };
+        let mut details = BTreeMap::new();
+        details.insert("address".to_string(), evm_oft_addr.clone());
+        details.insert("endpoint".to_string(), evm_profile.endpoint.clone());
+        steps.push(PlanStep {
+            step: steps.len() + 1,
+            chain: evm_profile.name.clone(),
+            action: format!("deploy {}", target.oft.flavour.contract_name()),
+            skip: recorded_addr.is_some(),
+            details,
+        });
+        diff.push(ResourceDiff::new(format!("{}.oft", evm_profile.name), recorded_addr.clone(), evm_oft_addr.clone()));
+        deployed.insert(evm_profile.name.clone(), Deployed::Evm(evm_oft_addr));
+    }
+
+    // 3. Peers, each registered under the remote chain's EID
+    for link in &config.links {
+        let remote_eid = config.eid(&link.to);
+        let remote_bytes = deployed[&link.to].to_bytes32()?;
+        let peer_hex = format!("0x{}", hex::encode(remote_bytes));
+        let mut details = BTreeMap::new();
+        let (action, current) = match &deployed[&link.from] {
+            Deployed::Solana(pda) => {
+                let peer_ix = solana_ops::build_set_peer_ix(&profile.solana, pda, &payer, remote_eid, remote_bytes)?;
+                details.insert("peer".to_string(), peer_hex.clone());
+                details.insert("data".to_string(), hex::encode(&peer_ix.data));
+                details.insert("accounts".to_string(), format_accounts(&peer_ix));
+                let (simulation, current) = if adapter_exists {
+                    let peer = OftClient::new(&profile.solana)?.peer_config(pda, remote_eid)?;
+                    (simulate(profile, &payer, &[peer_ix])?, peer.map(|p| format!("0x{}", hex::encode(p.peer_address))))
+                } else if recorded_pda.is_some() {
+                    ("not simulated: the recorded OFT store does not exist".to_string(), None)
+                } else {
+                    ("not simulated: the OFT store is created in step 1".to_string(), None)
+                };
+                details.insert("simulation".to_string(), simulation);
+                (format!("set_peer_config(eid {})", remote_eid), current)
+            }
+            Deployed::Evm(addr) => {
+                let current = if evm_existing.contains(&link.from) {
+                    evm_ops::read_peer(&config.evm_target(&link.from).profile, addr, remote_eid).await?
+                } else {
+                    None
+                };
+                details.insert("to".to_string(), addr.clone());
+                details.insert("calldata".to_string(), format!("0x{}", hex::encode(evm_ops::set_peer_calldata(remote_eid, remote_bytes))));
+                (format!("setPeer({}, {})", remote_eid, peer_hex), current)
+            }
+        };
+        steps.push(PlanStep {
+            step: steps.len() + 1,
+            chain: link.from.clone(),
+            action,
+            skip: current.as_deref().is_some_and(|p| p.eq_ignore_ascii_case(&peer_hex)),
+            details,
+        });
+        diff.push(ResourceDiff::new(format!("{}.peers[{}]", link.from, remote_eid), current, peer_hex));
+    }
+
+    // 4. Hand-over of the EVM OFTs to `delegate`, once the deployer is done wiring
+    for target in &config.evm {
+        let Some(delegate) = &target.oft.delegate else {
+            continue;
+        };
+        let evm_profile = &target.profile;
+        let Deployed::Evm(addr) = &deployed[&evm_profile.name] else {
+            unreachable!("deployment kind always matches chain kind");
+        };
+        let new_owner = evm_client::parse_address(delegate)?;
+        let (owner, endpoint_delegate) = if evm_existing.contains(&evm_profile.name) {
+            let (owner, endpoint_delegate) = evm_ops::read_ownership(evm_profile, addr).await?;
+            (Some(owner), Some(endpoint_delegate))
+        } else {
+            (None, None)
+        };
+        let mut details = BTreeMap::new();
+        details.insert("to".to_string(), addr.clone());
+        details.insert("calls".to_string(), "setDelegate, transferOwnership".to_string());
+        steps.push(PlanStep {
+            step: steps.len() + 1,
+            chain: evm_profile.name.clone(),
+            action: format!("hand over to {:?}", new_owner),
+            skip: owner == Some(new_owner) && endpoint_delegate == Some(new_owner),
+            details,
+        });
+        let desired = format!("{:?}", new_owner);
+        diff.push(ResourceDiff::new(format!("{}.owner", evm_profile.name), owner.map(|o| format!("{:?}", o)), desired.clone()));
+        diff.push(ResourceDiff::new(format!("{}.delegate", evm_profile.name), endpoint_delegate.map(|d| format!("{:?}", d)), desired));
+    }
+
+    let plan = Plan { network: network.clone(), steps, diff };
+    print_plan(&plan);
+
+    if let Some(dir) = plan_path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(plan_path, serde_json::to_string_pretty(&plan)?)?;
+    println!("\n📄 Machine-readable plan written to {}", plan_path.display());
+    Ok(())
+}
+
+fn simulate(profile: &NetworkProfile, payer: &Pubkey, instructions: &[solana_sdk::instruction::Instruction]) -> Result<String> {
+    let sim = solana_ops::simulate_ixs(&profile.solana, payer, instructions)?;
+    Ok(match sim.err {
+        None => format!("ok ({} log lines)", sim.logs.len()),
+        Some(err) => format!("error: {} | logs: {}", err, sim.logs.join(" / ")),
+    })
+}
+
+fn format_accounts(ix: &solana_sdk::instruction::Instruction) -> String {
+    ix.accounts
+        .iter()
+        .map(|a| {
+            let flags = match (a.is_writable, a.is_signer) {
+                (true, true) => "ws",
+                (true, false) => "w",
+                (false, true) => "s",
+                (false, false) => "r",
+            };
+            format!("{}[{}]", a.pubkey, flags)
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn print_plan(plan: &Plan) {
+    println!("\n📋 Deployment plan ({})", plan.network);
+    for step in &plan.steps {
+        let marker = if step.skip { "⏭️  skip" } else { "➕ run " };
+        println!("\n{} [{}] {}: {}", marker, step.step, step.chain, step.action);
+        for (key, value) in &step.details {
+            println!("      {:<10} {}", key, value);
+        }
+    }
+
+    println!("\n🔍 State diff");
+    for d in &plan.diff {
+        let symbol = match d.change {
+            Change::Create => "+",
+            Change::Update => "~",
+            Change::None => "=",
+        };
+        println!("  {} {:<28} {} -> {}", symbol, d.resource, d.current.as_deref().unwrap_or("(none)"), d.desired);
+    }
+}
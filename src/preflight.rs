@@ -0,0 +1,101 @@
+use anyhow::Result;
+
+/// Local and shared decimals of one chain's OFT, read from the deployment or planned.
+#[derive(Clone, Copy, Debug)]
+pub struct Decimals {
+    pub local: u8,
+    pub shared: u8,
+    pub deployed: bool,
+}
+
+impl Decimals {
+    /// Local-to-shared conversion rate; `None` if local < shared or the rate overflows u64.
+    pub fn ld2sd_rate(&self) -> Option<u64> {
+        self.local.checked_sub(self.shared).and_then(|d| 10u64.checked_pow(d as u32))
+    }
+}
+
+/// Decimals difference encoded by a power-of-ten conversion rate.
+pub fn rate_decimals(rate: u64) -> Result<u8> {
+    if rate == 0 || 10u64.pow(rate.ilog10()) != rate {
+        anyhow::bail!("decimalConversionRate {} is not a power of ten", rate);
+    }
+    Ok(rate.ilog10() as u8)
+}
+
+/// Prints every chain's `ld2sd_rate` and rejects meshes that would lose funds or fail sends:
+/// local decimals below the shared decimals (`InvalidDecimals` / `InvalidLocalDecimals`),
+/// or chains that disagree on the shared decimals, which scales amounts wrongly on receive.
+pub fn check_decimals(chains: &[(String, Decimals)]) -> Result<()> {
+    println!("🔢 Decimals preflight");
+    let mut errors = Vec::new();
+    for (chain, d) in chains {
+        let planned = if d.deployed { "" } else { " (planned)" };
+        match d.ld2sd_rate() {
+            Some(rate) => println!("   ✔ {:<20} local {:>2}, shared {}, ld2sd_rate {}{}", chain, d.local, d.shared, rate, planned),
+            None => {
+                println!("   ✘ {:<20} local {:>2}, shared {}{}", chain, d.local, d.shared, planned);
+                errors.push(format!("{}: {} local decimals cannot carry {} shared decimals", chain, d.local, d.shared));
+            }
+        }
+    }
+    if let Some((first, expected)) = chains.first() {
+        for (chain, d) in &chains[1..] {
+            if d.shared != expected.shared {
+                errors.push(format!(
+                    "{} uses {} shared decimals but {} uses {}; received amounts would be off by 10^{}",
+                    chain, d.shared, first, expected.shared, d.shared.abs_diff(expected.shared)
+                ));
+            }
+        }
+    }
+    if !errors.is_empty() {
+        anyhow::bail!("Decimals misconfigured:\n  {}", errors.join("\n  "));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decimals(local: u8, shared: u8) -> Decimals {
+        Decimals { local, shared, deployed: true }
+    }
+
+    #[test]
+    fn ld2sd_rate_scales_by_the_decimals_difference() {
+        assert_eq!(decimals(18, 6).ld2sd_rate(), Some(1_000_000_000_000));
+        assert_eq!(decimals(6, 6).ld2sd_rate(), Some(1));
+        assert_eq!(decimals(4, 6).ld2sd_rate(), None);
+        assert_eq!(decimals(30, 6).ld2sd_rate(), None);
+    }
+
+    #[test]
+    fn rate_decimals_needs_a_power_of_ten() {
+        assert_eq!(rate_decimals(1).unwrap(), 0);
+        assert_eq!(rate_decimals(1_000_000_000_000).unwrap(), 12);
+        assert!(rate_decimals(0).is_err());
+        assert!(rate_decimals(250).is_err());
+    }
+
+    #[test]
+    fn accepts_matching_shared_decimals() {
+        let chains = [("solana".to_string(), decimals(9, 6)), ("sepolia".to_string(), decimals(18, 6))];
+        assert!(check_decimals(&chains).is_ok());
+    }
+
+    #[test]
+    fn rejects_local_below_shared() {
+        let chains = [("solana".to_string(), decimals(4, 6)), ("sepolia".to_string(), decimals(18, 6))];
+        let err = check_decimals(&chains).unwrap_err().to_string();
+        assert!(err.contains("solana: 4 local decimals cannot carry 6 shared decimals"), "{}", err);
+    }
+
+    #[test]
+    fn rejects_mismatched_shared_decimals() {
+        let chains = [("solana".to_string(), decimals(9, 6)), ("sepolia".to_string(), decimals(18, 8))];
+        let err = check_decimals(&chains).unwrap_err().to_string();
+        assert!(err.contains("sepolia uses 8 shared decimals but solana uses 6"), "{}", err);
+    }
+}
@@ -0,0 +1,205 @@
+use clap::{Subcommand, ValueEnum};
+use serde::Deserialize;
+use anyhow::{Result, Context};
+use crate::deploy::Deployed;
+use crate::export::{self, Check, PeerField};
+use crate::oft_client::{OftClient, PeerConfig, PeerConfigParam, RateLimitParams, RateLimiter, SetPeerConfigParams};
+use crate::target::ChainArgs;
+
+/// Bucket size and refill rate of one direction, in local decimals.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct RateLimitSpec {
+    pub capacity: u64,
+    pub refill_per_second: u64,
+}
+
+/// Declared caps of one Solana peer; a missing direction means unlimited.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct PeerRateLimits {
+    #[serde(default)]
+    pub outbound: Option<RateLimitSpec>,
+    #[serde(default)]
+    pub inbound: Option<RateLimitSpec>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Direction {
+    /// Sends from Solana to the peer
+    Outbound,
+    /// Receives on Solana from the peer
+    Inbound,
+}
+
+impl Direction {
+    fn limiter(self, peer: &PeerConfig) -> &Option<RateLimiter> {
+        match self {
+            Direction::Outbound => &peer.outbound_rate_limiter,
+            Direction::Inbound => &peer.inbound_rate_limiter,
+        }
+    }
+
+    fn field(self) -> PeerField {
+        match self {
+            Direction::Outbound => PeerField::OutboundRateLimit,
+            Direction::Inbound => PeerField::InboundRateLimit,
+        }
+    }
+
+    fn param(self, params: Option<RateLimitParams>) -> PeerConfigParam {
+        match self {
+            Direction::Outbound => PeerConfigParam::OutboundRateLimit(params),
+            Direction::Inbound => PeerConfigParam::InboundRateLimit(params),
+        }
+    }
+}
+
+/// The Solana peer whose limiters are managed.
+#[derive(clap::Args)]
+pub struct RateLimitTarget {
+    #[command(flatten)]
+    chain: ChainArgs,
+    /// Remote EID of the peer, or its chain name in the state file
+    #[arg(long)]
+    peer: String,
+}
+
+/// `oft-cli rate-limit` subcommands.
+#[derive(Subcommand)]
+pub enum RateLimitAction {
+    /// Set capacity and/or refill rate; a new capacity also refills the bucket
+    Set {
+        #[command(flatten)]
+        target: RateLimitTarget,
+        #[arg(long, value_enum)]
+        direction: Direction,
+        #[arg(long, required_unless_present = "refill_per_second")]
+        capacity: Option<u64>,
+        #[arg(long)]
+        refill_per_second: Option<u64>,
+    },
+    /// Remove the limiter, leaving the direction unlimited
+    Clear {
+        #[command(flatten)]
+        target: RateLimitTarget,
+        #[arg(long, value_enum)]
+        direction: Direction,
+    },
+    /// Print both limiters with the tokens available now
+    Show {
+        #[command(flatten)]
+        target: RateLimitTarget,
+    },
+}
+
+/// Params that move `current` to `desired`, or `None` if they already match. Only a changed
+/// capacity is sent, since setting it refills the bucket.
+pub fn reconcile(current: &Option<RateLimiter>, desired: &Option<RateLimitSpec>) -> Option<Option<RateLimitParams>> {
+    match (current, desired) {
+        (None, None) => None,
+        (Some(_), None) => Some(None),
+        (None, Some(spec)) => Some(Some(RateLimitParams { capacity: Some(spec.capacity), refill_per_second: Some(spec.refill_per_second) })),
+        (Some(limiter), Some(spec)) => {
+            let capacity = (limiter.capacity != spec.capacity).then_some(spec.capacity);
+            let refill_per_second = (limiter.refill_per_second != spec.refill_per_second).then_some(spec.refill_per_second);
+            (capacity.is_some() || refill_per_second.is_some()).then_some(Some(RateLimitParams { capacity, refill_per_second }))
+        }
+    }
+}
+
+pub fn fmt_limiter(limiter: &Option<RateLimiter>, now: u64) -> String {
+    match limiter {
+        None => "unlimited".to_string(),
+        Some(l) => format!(
+            "{} of {} available (stored {}), +{}/s, last refill {}",
+            l.available(now), l.capacity, l.tokens, l.refill_per_second, l.last_refill_time
+        ),
+    }
+}
+
+pub async fn run(action: &RateLimitAction) -> Result<()> {
+    let target = match action {
+        RateLimitAction::Set { target, .. } | RateLimitAction::Clear { target, .. } | RateLimitAction::Show { target } => target,
+    };
+    let resolved = target.chain.resolve()?;
+    let Deployed::Solana(store) = resolved.chain.oft else {
+        anyhow::bail!("Rate limits are only managed on Solana; '{}' is an EVM chain", target.chain.chain);
+    };
+    let client = OftClient::new(&resolved.profile.solana)?;
+    let eid = resolved.peer_eid(&target.peer)?;
+
+    let current = client.peer_config(&store, eid)?;
+    let (config, check) = match action {
+        RateLimitAction::Show { .. } => {
+            let peer = current;
+            let now = client.unix_timestamp()?;
+            println!("🚦 {} -> EID {} (cluster time {})", target.chain.chain, eid, now);
+            match peer {
+                None => println!("   no peer config"),
+                Some(peer) => {
+                    println!("   outbound            {}", fmt_limiter(&peer.outbound_rate_limiter, now));
+                    println!("   inbound             {}", fmt_limiter(&peer.inbound_rate_limiter, now));
+                }
+            }
+            return Ok(());
+        }
+        RateLimitAction::Set { direction, capacity, refill_per_second, .. } => {
+            // A field left out keeps its current value, or 0 on a new limiter
+            let limiter = current.as_ref().and_then(|peer| direction.limiter(peer).clone());
+            let expected = (
+                capacity.unwrap_or(limiter.as_ref().map_or(0, |l| l.capacity)),
+                refill_per_second.unwrap_or(limiter.as_ref().map_or(0, |l| l.refill_per_second)),
+            );
+            let check = Check::peer(&store, eid, direction.field(), export::fmt_rate_limit(Some(expected)));
+            (direction.param(Some(RateLimitParams { capacity: *capacity, refill_per_second: *refill_per_second })), check)
+        }
+        RateLimitAction::Clear { direction, .. } => {
+            (direction.param(None), Check::peer(&store, eid, direction.field(), export::fmt_rate_limit(None)))
+        }
+    };
+
+    current.with_context(|| format!("No peer config for EID {}; set the peer first", eid))?;
+    let mut exporter = target.chain.deployment.export.exporter()?;
+    let mut submit = target.chain.deployment.solana_submit(&target.chain.chain, &mut exporter)?;
+    let admin = submit.solana_authority(|| Ok(client.oft_store(&store)?.admin))?;
+    let ix = client.set_peer_config_ix(&admin, &store, SetPeerConfigParams { remote_eid: eid, config })?;
+    if let Some(sig) = submit.solana(&client, &resolved.chain.name, &admin, &[ix], "set_peer_config", vec![check])? {
+        println!("✅ Rate limit updated! Tx: {}", sig);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(capacity: u64, refill_per_second: u64) -> Option<RateLimiter> {
+        Some(RateLimiter { capacity, tokens: capacity, refill_per_second, last_refill_time: 0 })
+    }
+
+    fn spec(capacity: u64, refill_per_second: u64) -> Option<RateLimitSpec> {
+        Some(RateLimitSpec { capacity, refill_per_second })
+    }
+
+    fn fields(params: Option<Option<RateLimitParams>>) -> Option<Option<(Option<u64>, Option<u64>)>> {
+        params.map(|p| p.map(|p| (p.capacity, p.refill_per_second)))
+    }
+
+    #[test]
+    fn reconcile_skips_matching_limiters() {
+        assert!(reconcile(&None, &None).is_none());
+        assert!(reconcile(&limiter(100, 10), &spec(100, 10)).is_none());
+    }
+
+    #[test]
+    fn reconcile_sends_only_changed_fields() {
+        assert_eq!(fields(reconcile(&limiter(100, 10), &spec(100, 20))), Some(Some((None, Some(20)))));
+        assert_eq!(fields(reconcile(&limiter(100, 10), &spec(200, 10))), Some(Some((Some(200), None))));
+        assert_eq!(fields(reconcile(&limiter(100, 10), &spec(200, 20))), Some(Some((Some(200), Some(20)))));
+    }
+
+    #[test]
+    fn reconcile_creates_and_clears_limiters() {
+        assert_eq!(fields(reconcile(&None, &spec(100, 10))), Some(Some((Some(100), Some(10)))));
+        assert_eq!(fields(reconcile(&limiter(100, 10), &None)), Some(None));
+    }
+}
@@ -0,0 +1,169 @@
+use clap::Subcommand;
+use serde::Deserialize;
+use std::path::Path;
+use anyhow::{Result, Context};
+use crate::network::Network;
+
+/// Registry compiled into the binary.
+const BUNDLED_REGISTRY: &str = include_str!("../registry/chains.toml");
+
+/// Local file whose entries override or extend the bundled registry.
+pub const LOCAL_REGISTRY_PATH: &str = "chains.toml";
+
+/// Queries against the chain registry.
+#[derive(Subcommand)]
+pub enum ChainsAction {
+    /// One line per chain: name, network, chain ID, EID and endpoint
+    List {
+        #[arg(long, value_enum)]
+        network: Option<Network>,
+    },
+    /// Every registry field of one chain
+    Show {
+        name: String,
+    },
+}
+
+/// LayerZero V2 deployments by chain name.
+#[derive(Debug, Deserialize)]
+pub struct Registry {
+    pub version: String,
+    pub chains: Vec<ChainInfo>,
+    /// Local file merged into the bundled registry, if any.
+    #[serde(skip)]
+    pub local_file: Option<String>,
+}
+
+/// Local overrides; the version is optional there.
+#[derive(Deserialize)]
+struct LocalRegistry {
+    #[serde(default)]
+    version: Option<String>,
+    #[serde(default)]
+    chains: Vec<ChainInfo>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChainFamily {
+    Evm,
+    Solana,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ChainInfo {
+    pub name: String,
+    pub kind: ChainFamily,
+    pub network: Network,
+    /// EVM chain ID; none for Solana.
+    #[serde(default)]
+    pub chain_id: Option<u32>,
+    pub eid: u32,
+    pub rpc_url: String,
+    /// EndpointV2 address or endpoint program ID.
+    pub endpoint: String,
+    /// Default send library (SendUln302, or the ULN program on Solana).
+    pub send_library: String,
+    /// Default receive library (ReceiveUln302, or the ULN program on Solana).
+    pub receive_library: String,
+    /// OFT program the token is deployed with (Solana only); the devtools example program
+    /// when omitted.
+    #[serde(default)]
+    pub oft_program: Option<String>,
+}
+
+impl ChainInfo {
+    /// `oft_program`, or the devtools example program.
+    pub fn oft_program(&self) -> String {
+        self.oft_program.clone().unwrap_or_else(crate::network::default_oft_program)
+    }
+}
+
+impl Registry {
+    /// The bundled registry merged with `local_path` if it exists.
+    pub fn load(local_path: &Path) -> Result<Self> {
+        let mut registry: Registry = toml::from_str(BUNDLED_REGISTRY).context("Bundled chain registry is invalid")?;
+        if !local_path.exists() {
+            return Ok(registry);
+        }
+        let raw = std::fs::read_to_string(local_path)
+            .with_context(|| format!("Failed to read chain registry {}", local_path.display()))?;
+        let local: LocalRegistry = toml::from_str(&raw)
+            .with_context(|| format!("Invalid chain registry {}", local_path.display()))?;
+        for chain in local.chains {
+            match registry.chains.iter_mut().find(|c| c.name == chain.name) {
+                Some(existing) => *existing = chain,
+                None => registry.chains.push(chain),
+            }
+        }
+        if let Some(version) = local.version {
+            registry.version = format!("{}+{}", registry.version, version);
+        }
+        registry.local_file = Some(local_path.display().to_string());
+        Ok(registry)
+    }
+
+    /// Finds a chain by name, listing the known ones if it is missing.
+    pub fn chain(&self, name: &str) -> Result<&ChainInfo> {
+        self.chains.iter().find(|c| c.name == name).with_context(|| {
+            let known: Vec<&str> = self.chains.iter().map(|c| c.name.as_str()).collect();
+            format!("Unknown chain '{}'. Known: {}", name, known.join(", "))
+        })
+    }
+
+    pub fn on_network(&self, network: Network) -> impl Iterator<Item = &ChainInfo> {
+        self.chains.iter().filter(move |c| c.network == network)
+    }
+}
+
+impl ChainFamily {
+    fn name(&self) -> &'static str {
+        match self {
+            ChainFamily::Evm => "evm",
+            ChainFamily::Solana => "solana",
+        }
+    }
+}
+
+pub fn run(action: &ChainsAction) -> Result<()> {
+    let registry = Registry::load(Path::new(LOCAL_REGISTRY_PATH))?;
+    match &registry.local_file {
+        Some(file) => println!("📚 Chain registry {} (with overrides from {})", registry.version, file),
+        None => println!("📚 Chain registry {}", registry.version),
+    }
+    match action {
+        ChainsAction::List { network } => {
+            println!("\n{:<20} {:<8} {:<8} {:>10} {:>6}  ENDPOINT", "NAME", "KIND", "NETWORK", "CHAIN ID", "EID");
+            let chains = registry.chains.iter().filter(|c| network.is_none_or(|n| c.network == n));
+            for chain in chains {
+                println!(
+                    "{:<20} {:<8} {:<8} {:>10} {:>6}  {}",
+                    chain.name,
+                    chain.kind.name(),
+                    chain.network.name(),
+                    chain.chain_id.map(|id| id.to_string()).unwrap_or_else(|| "-".to_string()),
+                    chain.eid,
+                    chain.endpoint
+                );
+            }
+        }
+        ChainsAction::Show { name } => {
+            let chain = registry.chain(name)?;
+            println!("\n🔗 {}", chain.name);
+            println!("   kind                {}", chain.kind.name());
+            println!("   network             {}", chain.network.name());
+            if let Some(chain_id) = chain.chain_id {
+                println!("   chain ID            {}", chain_id);
+            }
+            println!("   EID                 {}", chain.eid);
+            println!("   RPC                 {}", chain.rpc_url);
+            println!("   endpoint            {}", chain.endpoint);
+            println!("   send library        {}", chain.send_library);
+            println!("   receive library     {}", chain.receive_library);
+            if let ChainFamily::Solana = chain.kind {
+                println!("   OFT program         {}", chain.oft_program());
+            }
+        }
+    }
+    Ok(())
+}
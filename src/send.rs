@@ -0,0 +1,355 @@
+use std::str::FromStr;
+use ethabi::ethereum_types::U256;
+use solana_sdk::{compute_budget::ComputeBudgetInstruction, pubkey::Pubkey};
+use anyhow::{Result, Context};
+use crate::deploy::Deployed;
+use crate::evm_ops::{self, OftFlavour, SendParam};
+use crate::network::{EvmProfile, SolanaProfile};
+use crate::oft_client::{OftClient, QuoteParams, SendParams, SendResult};
+use crate::signer::KeySource;
+use crate::status::AuditChain;
+use crate::target::DeploymentArgs;
+use crate::{evm_client, options, spl};
+
+/// Compute units for `send`: the OFT debit plus the endpoint, ULN, executor and DVN CPIs.
+const SEND_COMPUTE_UNITS: u32 = 400_000;
+
+const BPS_DENOMINATOR: u128 = 10_000;
+
+/// A transfer between two chains of a recorded deployment.
+#[derive(clap::Args)]
+pub struct SendArgs {
+    /// Source chain, as named in the state file
+    #[arg(long)]
+    pub from: String,
+    /// Destination chain, as named in the state file
+    #[arg(long)]
+    pub to: String,
+    /// Amount in whole tokens, e.g. 1.5
+    #[arg(long)]
+    pub amount: String,
+    /// Recipient on the destination chain: an EVM address or a Solana wallet pubkey
+    #[arg(long)]
+    pub recipient: String,
+    /// How far below the quoted received amount the transfer may land before it reverts, in bps
+    #[arg(long, default_value_t = 50)]
+    pub slippage_bps: u16,
+    /// Extra type-3 options on top of the enforced ones, hex (see `oft-cli options build`)
+    #[arg(long, default_value = "")]
+    pub options: String,
+    /// Solana token account to send from; defaults to the signer's associated token account
+    #[arg(long)]
+    pub token_source: Option<Pubkey>,
+    /// Print the quote without sending
+    #[arg(long)]
+    pub quote_only: bool,
+    #[command(flatten)]
+    pub deployment: DeploymentArgs,
+}
+
+/// `quote_oft`/`quoteOFT` and `quote_send`/`quoteSend` of one transfer, in the source chain's local decimals.
+struct Quote {
+    decimals: u8,
+    amount_ld: u128,
+    dust_ld: u128,
+    min_limit_ld: u128,
+    max_limit_ld: u128,
+    fee_details: Vec<(i128, String)>,
+    sent_ld: u128,
+    received_ld: u128,
+    native_fee: u128,
+}
+
+impl Quote {
+    /// Received amount less `slippage_bps`, enforced on-chain as `min_amount_ld`.
+    fn min_received(&self, slippage_bps: u16) -> u128 {
+        self.received_ld * (BPS_DENOMINATOR - slippage_bps as u128) / BPS_DENOMINATOR
+    }
+
+    fn print(&self, slippage_bps: u16, native: &str, native_decimals: u8) {
+        println!("📊 Quote");
+        println!("   amount              {}", format_amount(self.amount_ld, self.decimals));
+        if self.dust_ld > 0 {
+            println!("   dust                {} (below shared-decimals precision, stays with the sender)", format_amount(self.dust_ld, self.decimals));
+        }
+        for (fee, description) in &self.fee_details {
+            let fee = match u128::try_from(*fee) {
+                Ok(fee) => format_amount(fee, self.decimals),
+                Err(_) => format!("-{}", format_amount(fee.unsigned_abs(), self.decimals)),
+            };
+            println!("   fee                 {} ({})", fee, description);
+        }
+        println!("   sent                {}", format_amount(self.sent_ld, self.decimals));
+        println!("   received            {}", format_amount(self.received_ld, self.decimals));
+        println!("   min received        {} ({} bps slippage)", format_amount(self.min_received(slippage_bps), self.decimals), slippage_bps);
+        println!("   limits              {} - {}", format_amount(self.min_limit_ld, self.decimals), format_amount(self.max_limit_ld, self.decimals));
+        println!("   native fee          {} {}", format_amount(self.native_fee, native_decimals), native);
+    }
+
+    fn check(&self) -> Result<()> {
+        if self.received_ld == 0 {
+            anyhow::bail!("Nothing would arrive: the amount is all dust or fees");
+        }
+        if self.sent_ld < self.min_limit_ld || self.sent_ld > self.max_limit_ld {
+            anyhow::bail!(
+                "Amount sent {} is outside the OFT limits {} - {}",
+                format_amount(self.sent_ld, self.decimals),
+                format_amount(self.min_limit_ld, self.decimals),
+                format_amount(self.max_limit_ld, self.decimals)
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Parses a decimal amount such as `1.5` into local decimals.
+fn parse_amount(value: &str, decimals: u8) -> Result<u128> {
+    let (whole, fraction) = value.split_once('.').unwrap_or((value, ""));
+    let digits = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+    if (whole.is_empty() && fraction.is_empty()) || !digits(whole) || !digits(fraction) {
+        anyhow::bail!("Invalid amount '{}'", value);
+    }
+    if fraction.len() > decimals as usize {
+        anyhow::bail!("Amount {} has more than the token's {} decimals", value, decimals);
+    }
+    let scaled = format!("{}{:0<width$}", whole, fraction, width = decimals as usize);
+    let amount: u128 = scaled.parse().with_context(|| format!("Amount {} is too large", value))?;
+    if amount == 0 {
+        anyhow::bail!("Amount must be positive");
+    }
+    Ok(amount)
+}
+
+/// Formats an amount in local decimals as a decimal number, without trailing zeros.
+fn format_amount(amount: u128, decimals: u8) -> String {
+    let Some(scale) = 10u128.checked_pow(decimals as u32) else {
+        return amount.to_string();
+    };
+    match amount % scale {
+        0 => (amount / scale).to_string(),
+        fraction => {
+            let fraction = format!("{:0width$}", fraction, width = decimals as usize);
+            format!("{}.{}", amount / scale, fraction.trim_end_matches('0'))
+        }
+    }
+}
+
+fn saturating_u128(value: U256) -> u128 {
+    value.min(U256::from(u128::MAX)).as_u128()
+}
+
+fn find_chain<'a>(chains: &'a [AuditChain], name: &str, deployment: &DeploymentArgs) -> Result<&'a AuditChain> {
+    chains.iter().find(|c| c.name == name).ok_or_else(|| {
+        let known: Vec<&str> = chains.iter().map(|c| c.name.as_str()).collect();
+        anyhow::anyhow!("Chain '{}' is not in {}. Known: {}", name, deployment.state_path().display(), known.join(", "))
+    })
+}
+
+/// Recipient as bytes32 for the destination's address format, so a Solana pubkey can't be
+/// sent to an EVM chain or a padded EVM address to Solana.
+fn parse_recipient(recipient: &str, to: &AuditChain) -> Result<[u8; 32]> {
+    match to.oft {
+        Deployed::Solana(_) => Ok(Pubkey::from_str(recipient)
+            .with_context(|| format!("Recipient on {} must be a base58 wallet pubkey, got '{}'", to.name, recipient))?
+            .to_bytes()),
+        Deployed::Evm(_) => {
+            let address = evm_client::parse_address(recipient)
+                .with_context(|| format!("Recipient on {} must be an EVM address", to.name))?;
+            let mut padded = [0u8; 32];
+            padded[12..].copy_from_slice(address.as_bytes());
+            Ok(padded)
+        }
+    }
+}
+
+/// Quotes the transfer, shows fees, dust and the slippage floor, then sends it with
+/// `min_amount_ld` enforced and prints the message GUID.
+pub async fn run(args: &SendArgs) -> Result<()> {
+    if args.deployment.export.export_unsigned.is_some() {
+        anyhow::bail!("Transfers can't be exported; only admin actions can");
+    }
+    if args.slippage_bps as u128 > BPS_DENOMINATOR {
+        anyhow::bail!("--slippage-bps must be at most {}", BPS_DENOMINATOR);
+    }
+    let (profile, chains) = args.deployment.load()?;
+    let from = find_chain(&chains, &args.from, &args.deployment)?;
+    let to = find_chain(&chains, &args.to, &args.deployment)?;
+    if from.name == to.name {
+        anyhow::bail!("--from and --to are both {}", from.name);
+    }
+    let recipient = parse_recipient(&args.recipient, to)?;
+    let extra_options = match args.options.as_str() {
+        "" => Vec::new(),
+        hex => options::parse_type_3(hex)?,
+    };
+    let signers = args.deployment.signer.load()?;
+
+    println!("📦 Sending {} from {} to {} (EID {})...", args.amount, from.name, to.name, to.eid);
+    let guid = match (&from.oft, &from.evm) {
+        (Deployed::Solana(store), _) => send_solana(args, &profile.solana, &signers.solana(&from.name), store, to.eid, recipient, extra_options)?,
+        (Deployed::Evm(oft), Some(evm)) => send_evm(args, evm, &signers.evm(&from.name), oft, to.eid, recipient, extra_options).await?,
+        (Deployed::Evm(_), None) => unreachable!("EVM chains always carry a profile"),
+    };
+    if let Some(guid) = guid {
+        println!("🆔 GUID: 0x{}", hex::encode(guid));
+    }
+    Ok(())
+}
+
+fn send_solana(args: &SendArgs, profile: &SolanaProfile, key: &KeySource, oft_store: &Pubkey, dst_eid: u32, to: [u8; 32], options: Vec<u8>) -> Result<Option<[u8; 32]>> {
+    let client = OftClient::new(profile)?;
+    let signer = key.solana_signer()?;
+    let me = signer.pubkey();
+    let store = client.oft_store(oft_store)?;
+    let (token_program, mint) = client.mint(&store.token_mint)?;
+    let amount_ld: u64 = parse_amount(&args.amount, mint.decimals)?
+        .try_into()
+        .with_context(|| format!("Amount {} does not fit in a u64", args.amount))?;
+
+    let params = QuoteParams { dst_eid, to, amount_ld, min_amount_ld: 0, options, compose_msg: None, pay_in_lz_token: false };
+    let oft_quote = client.quote_oft(&me, oft_store, &params)?;
+    let fee = client.quote_send(&me, oft_store, &params)?;
+    let quote = Quote {
+        decimals: mint.decimals,
+        amount_ld: amount_ld.into(),
+        dust_ld: (amount_ld - store.remove_dust(amount_ld)).into(),
+        min_limit_ld: oft_quote.oft_limits.min_amount_ld.into(),
+        max_limit_ld: oft_quote.oft_limits.max_amount_ld.into(),
+        fee_details: oft_quote.oft_fee_details.into_iter().map(|f| (f.fee_amount_ld.into(), f.description)).collect(),
+        sent_ld: oft_quote.oft_receipt.amount_sent_ld.into(),
+        received_ld: oft_quote.oft_receipt.amount_received_ld.into(),
+        native_fee: fee.native_fee.into(),
+    };
+    quote.print(args.slippage_bps, "SOL", 9);
+    quote.check()?;
+    if args.quote_only {
+        return Ok(None);
+    }
+
+    let token_source = args.token_source.unwrap_or_else(|| spl::associated_token_address(&me, &token_program, &store.token_mint));
+    let balance = client.token_balance(&token_source)?;
+    if (balance as u128) < quote.sent_ld {
+        anyhow::bail!("{} holds {}, less than the {} sent", token_source, format_amount(balance.into(), mint.decimals), format_amount(quote.sent_ld, mint.decimals));
+    }
+    let send = SendParams {
+        dst_eid,
+        to,
+        amount_ld,
+        min_amount_ld: quote.min_received(args.slippage_bps) as u64,
+        options: params.options,
+        compose_msg: None,
+        native_fee: fee.native_fee,
+        lz_token_fee: 0,
+    };
+    let instructions = vec![
+        ComputeBudgetInstruction::set_compute_unit_limit(SEND_COMPUTE_UNITS),
+        client.send_ix(&me, oft_store, &token_source, &send)?,
+    ];
+    println!("🚀 Sending from {}...", token_source);
+    let sig = client.send_transaction(&instructions, signer.as_ref(), &[])?;
+    println!("✅ Tx: {}", sig);
+    let result: SendResult = client.transaction_return(&sig)?;
+    println!("   received on arrival {}", format_amount(result.oft_receipt.amount_received_ld.into(), mint.decimals));
+    println!("   nonce               {}", result.msg_receipt.nonce);
+    println!("   native fee paid     {} SOL", format_amount(result.msg_receipt.fee.native_fee.into(), 9));
+    Ok(Some(result.msg_receipt.guid))
+}
+
+async fn send_evm(args: &SendArgs, profile: &EvmProfile, key: &KeySource, oft: &str, dst_eid: u32, to: [u8; 32], options: Vec<u8>) -> Result<Option<[u8; 32]>> {
+    let decimals = evm_ops::oft_decimals(profile, &OftFlavour::Oft, Some(oft)).await?;
+    if !decimals.deployed {
+        anyhow::bail!("No OFT deployed at {} on {}", oft, profile.name);
+    }
+    let rate = decimals.ld2sd_rate().context("OFT local decimals are below its shared decimals")?;
+    let amount_ld = parse_amount(&args.amount, decimals.local)?;
+
+    let mut param = SendParam { dst_eid, to, amount_ld: U256::from(amount_ld), min_amount_ld: U256::zero(), extra_options: options };
+    let oft_quote = evm_ops::quote_oft_evm(profile, oft, &param).await?;
+    let native_fee = evm_ops::quote_send_evm(profile, oft, &param).await?;
+    let quote = Quote {
+        decimals: decimals.local,
+        amount_ld,
+        dust_ld: amount_ld % rate as u128,
+        min_limit_ld: saturating_u128(oft_quote.min_amount_ld),
+        max_limit_ld: saturating_u128(oft_quote.max_amount_ld),
+        fee_details: oft_quote.fee_details,
+        sent_ld: saturating_u128(oft_quote.amount_sent_ld),
+        received_ld: saturating_u128(oft_quote.amount_received_ld),
+        native_fee: saturating_u128(native_fee),
+    };
+    quote.print(args.slippage_bps, "(native)", 18);
+    quote.check()?;
+    if args.quote_only {
+        return Ok(None);
+    }
+
+    param.min_amount_ld = U256::from(quote.min_received(args.slippage_bps));
+    let (tx_hash, guid) = evm_ops::send_evm(profile, key, oft, &param, oft_quote.amount_sent_ld, native_fee).await?;
+    println!("✅ Tx: {}", tx_hash);
+    Ok(Some(guid))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_decimal_amounts() {
+        assert_eq!(parse_amount("1.5", 6).unwrap(), 1_500_000);
+        assert_eq!(parse_amount("1.5", 18).unwrap(), 1_500_000_000_000_000_000);
+        assert_eq!(parse_amount("2", 6).unwrap(), 2_000_000);
+        assert_eq!(parse_amount(".25", 2).unwrap(), 25);
+        assert_eq!(parse_amount("0.000001", 6).unwrap(), 1);
+    }
+
+    #[test]
+    fn rejects_more_fraction_digits_than_decimals() {
+        assert_eq!(parse_amount("0.0000001", 6).unwrap_err().to_string(), "Amount 0.0000001 has more than the token's 6 decimals");
+        assert!(parse_amount("1.5", 0).is_err());
+    }
+
+    #[test]
+    fn rejects_empty_and_zero_amounts() {
+        assert_eq!(parse_amount("", 6).unwrap_err().to_string(), "Invalid amount ''");
+        assert_eq!(parse_amount(".", 6).unwrap_err().to_string(), "Invalid amount '.'");
+        assert_eq!(parse_amount("0", 6).unwrap_err().to_string(), "Amount must be positive");
+        assert_eq!(parse_amount("0.000", 6).unwrap_err().to_string(), "Amount must be positive");
+        assert!(parse_amount("-1", 6).is_err());
+        assert!(parse_amount("1e6", 6).is_err());
+    }
+
+    #[test]
+    fn rejects_amounts_beyond_u128() {
+        // u128::MAX is 340282366920938463463374607431768211455, about 3.4e20 at 18 decimals
+        assert_eq!(parse_amount("340282366920938463464", 18).unwrap_err().to_string(), "Amount 340282366920938463464 is too large");
+        assert_eq!(parse_amount("340282366920938463463.374607431768211455", 18).unwrap(), u128::MAX);
+    }
+
+    #[test]
+    fn formats_without_trailing_zeros() {
+        assert_eq!(format_amount(1_500_000, 6), "1.5");
+        assert_eq!(format_amount(2_000_000, 6), "2");
+        assert_eq!(format_amount(1, 6), "0.000001");
+        assert_eq!(format_amount(1_050_000_000_000_000_000, 18), "1.05");
+        assert_eq!(format_amount(0, 9), "0");
+        assert_eq!(format_amount(42, 0), "42");
+    }
+
+    #[test]
+    fn min_received_applies_slippage() {
+        let quote = Quote {
+            decimals: 6,
+            amount_ld: 1_000_000,
+            dust_ld: 0,
+            min_limit_ld: 0,
+            max_limit_ld: u128::MAX,
+            fee_details: Vec::new(),
+            sent_ld: 1_000_000,
+            received_ld: 1_000_000,
+            native_fee: 0,
+        };
+        assert_eq!(quote.min_received(0), 1_000_000);
+        assert_eq!(quote.min_received(50), 995_000);
+        assert_eq!(quote.min_received(10_000), 0);
+    }
+}
@@ -0,0 +1,593 @@
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use aes::Aes128;
+use ctr::cipher::{NewCipher, StreamCipher};
+use ethabi::ethereum_types::Address;
+use hmac::Hmac;
+use libsecp256k1::{Message, PublicKey, RecoveryId, SecretKey};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::Sha256;
+use solana_sdk::{
+    bs58,
+    keccak,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature},
+    signer::{Signer, SignerError},
+};
+use anyhow::{Result, Context};
+use crate::{solana_ops, utils};
+
+/// Per-chain signers read from `--signers` when the file exists.
+pub const DEFAULT_SIGNERS_PATH: &str = "signers.toml";
+
+/// Environment variable EVM chains sign with when no signer is configured for them.
+pub const DEFAULT_EVM_KEY_ENV: &str = "PRIVATE_KEY";
+
+/// Where a signing key comes from, in a manifest or a signers file.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeySource {
+    /// Environment variable holding a hex EVM private key, or a Solana keypair as a JSON byte
+    /// array or base58 string.
+    Env(String),
+    /// Path to a Solana keypair JSON file (`~` is expanded).
+    Keypair(String),
+    /// Encrypted EVM JSON keystore (Web3 secret storage v3, as written by geth or `cast wallet`).
+    Keystore(KeystoreSource),
+    /// Key held by a remote signing service.
+    Remote(RemoteSource),
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct KeystoreSource {
+    /// Path to the keystore JSON (`~` is expanded).
+    pub path: String,
+    /// Environment variable holding the password; prompted for when unset.
+    #[serde(default)]
+    pub password_env: Option<String>,
+}
+
+/// A signing service speaking two JSON endpoints, each taking `key_id` and a `scheme` of
+/// `ed25519` (Solana) or `secp256k1` (EVM):
+///
+/// - `POST {url}/public-key` returns `{"public_key": "0x.."}`: 32 bytes for ed25519, a SEC1
+///   encoded key for secp256k1.
+/// - `POST {url}/sign` with a hex `payload` returns `{"signature": "0x.."}`: 64 bytes over the
+///   serialized message for ed25519, 65 bytes `r || s || v` over the 32-byte digest for secp256k1.
+///
+/// Every signature is checked against the public key before it is used.
+#[derive(Clone, Debug, Deserialize)]
+pub struct RemoteSource {
+    pub url: String,
+    pub key_id: String,
+    /// Environment variable holding a bearer token for the service.
+    #[serde(default)]
+    pub token_env: Option<String>,
+}
+
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Scheme {
+    Ed25519,
+    Secp256k1,
+}
+
+/// Signer configuration shared by the commands acting on a deployment.
+#[derive(clap::Args)]
+pub struct SignerArgs {
+    /// TOML file mapping chain names (`solana` or an EVM profile name) to signers; chains
+    /// without an entry use --keypair on Solana and PRIVATE_KEY on EVM
+    #[arg(long, default_value = DEFAULT_SIGNERS_PATH)]
+    pub signers: PathBuf,
+    /// Solana keypair for chains the signers file does not configure
+    #[arg(long, default_value = solana_ops::DEFAULT_KEYPAIR_PATH)]
+    pub keypair: String,
+}
+
+/// Signer of every chain, by name.
+pub struct Signers {
+    chains: BTreeMap<String, KeySource>,
+    keypair: String,
+}
+
+impl SignerArgs {
+    pub fn load(&self) -> Result<Signers> {
+        let chains = if self.signers.exists() {
+            let raw = std::fs::read_to_string(&self.signers)
+                .with_context(|| format!("Failed to read signers file {}", self.signers.display()))?;
+            toml::from_str(&raw).with_context(|| format!("Invalid signers file {}", self.signers.display()))?
+        } else {
+            BTreeMap::new()
+        };
+        Ok(Signers { chains, keypair: self.keypair.clone() })
+    }
+}
+
+impl Signers {
+    pub fn solana(&self, chain: &str) -> KeySource {
+        self.chains.get(chain).cloned().unwrap_or_else(|| KeySource::Keypair(self.keypair.clone()))
+    }
+
+    pub fn evm(&self, chain: &str) -> KeySource {
+        self.chains.get(chain).cloned().unwrap_or_else(|| KeySource::Env(DEFAULT_EVM_KEY_ENV.to_string()))
+    }
+}
+
+impl KeySource {
+    pub fn solana_signer(&self) -> Result<Box<dyn Signer>> {
+        match self {
+            KeySource::Keypair(path) => Ok(Box::new(solana_ops::load_keypair(path)?)),
+            KeySource::Env(var) => {
+                let value = env_var(var)?;
+                let value = value.trim();
+                let bytes = if value.starts_with('[') {
+                    serde_json::from_str::<Vec<u8>>(value).with_context(|| format!("{} is not a JSON keypair", var))?
+                } else {
+                    bs58::decode(value).into_vec().with_context(|| format!("{} is not a base58 keypair", var))?
+                };
+                let keypair = Keypair::from_bytes(&bytes).map_err(|e| anyhow::anyhow!("Invalid keypair in {}: {}", var, e))?;
+                Ok(Box::new(keypair))
+            }
+            KeySource::Remote(remote) => {
+                let public = block_on(remote.public_key(Scheme::Ed25519))?;
+                let pubkey = Pubkey::try_from(public.as_slice())
+                    .map_err(|_| anyhow::anyhow!("Remote signer returned a {}-byte ed25519 key", public.len()))?;
+                Ok(Box::new(RemoteSolanaSigner { remote: remote.clone(), pubkey }))
+            }
+            KeySource::Keystore(keystore) => anyhow::bail!("EVM keystore {} can't sign Solana transactions", keystore.path),
+        }
+    }
+
+    pub async fn evm_signer(&self) -> Result<EvmSigner> {
+        match self {
+            KeySource::Env(var) => {
+                let key = utils::parse_hex(env_var(var)?.trim()).with_context(|| format!("{} is not a hex private key", var))?;
+                let key = SecretKey::parse_slice(&key).map_err(|e| anyhow::anyhow!("Invalid private key in {}: {:?}", var, e))?;
+                Ok(EvmSigner::Local(key))
+            }
+            KeySource::Keystore(keystore) => Ok(EvmSigner::Local(keystore.unlock()?)),
+            KeySource::Remote(remote) => {
+                let public = remote.public_key(Scheme::Secp256k1).await?;
+                let public = PublicKey::parse_slice(&public, None)
+                    .map_err(|e| anyhow::anyhow!("Remote signer returned an invalid secp256k1 key: {:?}", e))?;
+                Ok(EvmSigner::Remote { remote: remote.clone(), address: address_of(&public) })
+            }
+            KeySource::Keypair(path) => anyhow::bail!("Solana keypair {} can't sign EVM transactions", path),
+        }
+    }
+}
+
+fn env_var(var: &str) -> Result<String> {
+    std::env::var(var).with_context(|| format!("Environment variable {} is not set", var))
+}
+
+/// Runs `future` from sync code on the multi-threaded runtime, as the Solana RPC client does.
+fn block_on<F: Future>(future: F) -> F::Output {
+    tokio::task::block_in_place(|| tokio::runtime::Handle::current().block_on(future))
+}
+
+/// Ethereum address of a secp256k1 key: the last 20 bytes of keccak(x || y).
+pub fn address_of(public: &PublicKey) -> Address {
+    let hash = keccak::hash(&public.serialize()[1..]);
+    Address::from_slice(&hash.as_ref()[12..])
+}
+
+/// Signs EVM transactions with a key held in memory or by a remote signer.
+pub enum EvmSigner {
+    Local(SecretKey),
+    Remote { remote: RemoteSource, address: Address },
+}
+
+impl EvmSigner {
+    pub fn address(&self) -> Address {
+        match self {
+            EvmSigner::Local(key) => address_of(&PublicKey::from_secret_key(key)),
+            EvmSigner::Remote { address, .. } => *address,
+        }
+    }
+
+    /// Signs a 32-byte digest, returning `r || s` and the recovery id.
+    pub async fn sign_digest(&self, digest: &[u8; 32]) -> Result<([u8; 64], u8)> {
+        let message = Message::parse(digest);
+        match self {
+            EvmSigner::Local(key) => {
+                let (signature, recovery_id) = libsecp256k1::sign(&message, key);
+                Ok((signature.serialize(), recovery_id.serialize()))
+            }
+            EvmSigner::Remote { remote, address } => {
+                let bytes = remote.sign(Scheme::Secp256k1, digest).await?;
+                if bytes.len() != 65 {
+                    anyhow::bail!("Remote signer returned a {}-byte secp256k1 signature, expected 65", bytes.len());
+                }
+                // Accept both raw recovery ids and legacy `v` values
+                let v = if bytes[64] >= 27 { bytes[64] - 27 } else { bytes[64] };
+                let signature = libsecp256k1::Signature::parse_standard_slice(&bytes[..64])
+                    .map_err(|e| anyhow::anyhow!("Remote signer returned an invalid signature: {:?}", e))?;
+                let recovery_id = RecoveryId::parse(v).map_err(|e| anyhow::anyhow!("Remote signer returned an invalid v: {:?}", e))?;
+                let recovered = libsecp256k1::recover(&message, &signature, &recovery_id)
+                    .map_err(|e| anyhow::anyhow!("Remote signature does not recover: {:?}", e))?;
+                if address_of(&recovered) != *address {
+                    anyhow::bail!("Remote signer signed with {:?}, expected {:?}", address_of(&recovered), address);
+                }
+                Ok((signature.serialize(), v))
+            }
+        }
+    }
+}
+
+impl RemoteSource {
+    async fn post(&self, endpoint: &str, body: Value) -> Result<Value> {
+        let url = format!("{}/{}", self.url.trim_end_matches('/'), endpoint);
+        let mut request = reqwest::Client::new().post(&url).json(&body);
+        if let Some(var) = &self.token_env {
+            request = request.bearer_auth(env_var(var)?);
+        }
+        let response = request.send().await.with_context(|| format!("Request to remote signer {} failed", url))?;
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            anyhow::bail!("Remote signer {} returned {}: {}", url, status, text);
+        }
+        response.json().await.with_context(|| format!("Remote signer {} returned a non-JSON response", url))
+    }
+
+    async fn public_key(&self, scheme: Scheme) -> Result<Vec<u8>> {
+        let response = self.post("public-key", json!({ "key_id": self.key_id, "scheme": scheme })).await?;
+        let key = response["public_key"].as_str().context("Remote signer response has no public_key")?;
+        utils::parse_hex(key)
+    }
+
+    async fn sign(&self, scheme: Scheme, payload: &[u8]) -> Result<Vec<u8>> {
+        let body = json!({ "key_id": self.key_id, "scheme": scheme, "payload": format!("0x{}", hex::encode(payload)) });
+        let response = self.post("sign", body).await?;
+        let signature = response["signature"].as_str().context("Remote signer response has no signature")?;
+        utils::parse_hex(signature)
+    }
+}
+
+/// Solana signer backed by a remote signing service.
+struct RemoteSolanaSigner {
+    remote: RemoteSource,
+    pubkey: Pubkey,
+}
+
+impl Signer for RemoteSolanaSigner {
+    fn try_pubkey(&self) -> Result<Pubkey, SignerError> {
+        Ok(self.pubkey)
+    }
+
+    fn try_sign_message(&self, message: &[u8]) -> Result<Signature, SignerError> {
+        let bytes = block_on(self.remote.sign(Scheme::Ed25519, message)).map_err(|e| SignerError::Custom(format!("{:#}", e)))?;
+        let signature = Signature::try_from(bytes.as_slice())
+            .map_err(|_| SignerError::Custom(format!("Remote signer returned a {}-byte ed25519 signature", bytes.len())))?;
+        if !signature.verify(self.pubkey.as_ref(), message) {
+            return Err(SignerError::Custom(format!("Remote signature does not verify against {}", self.pubkey)));
+        }
+        Ok(signature)
+    }
+
+    fn is_interactive(&self) -> bool {
+        false
+    }
+}
+
+/// Keystores already unlocked in this run, so each password is asked for once.
+static UNLOCKED: Mutex<BTreeMap<PathBuf, SecretKey>> = Mutex::new(BTreeMap::new());
+
+impl KeystoreSource {
+    fn unlock(&self) -> Result<SecretKey> {
+        let path = PathBuf::from(shellexpand::tilde(&self.path).into_owned());
+        if let Some(key) = UNLOCKED.lock().expect("keystore cache poisoned").get(&path) {
+            return Ok(*key);
+        }
+        let raw = std::fs::read_to_string(&path).with_context(|| format!("Failed to read keystore {}", path.display()))?;
+        let password = match &self.password_env {
+            Some(var) => env_var(var)?,
+            None => rpassword::prompt_password(format!("🔐 Password for {}: ", path.display()))
+                .context("Failed to read the keystore password")?,
+        };
+        println!("🔓 Unlocking {}...", path.display());
+        let key = decrypt_keystore(&raw, password.as_bytes()).with_context(|| format!("Failed to unlock {}", path.display()))?;
+        UNLOCKED.lock().expect("keystore cache poisoned").insert(path, key);
+        Ok(key)
+    }
+}
+
+#[derive(Deserialize)]
+struct Keystore {
+    #[serde(alias = "Crypto")]
+    crypto: KeystoreCrypto,
+}
+
+#[derive(Deserialize)]
+struct KeystoreCrypto {
+    cipher: String,
+    cipherparams: CipherParams,
+    ciphertext: String,
+    kdf: String,
+    kdfparams: Value,
+    mac: String,
+}
+
+#[derive(Deserialize)]
+struct CipherParams {
+    iv: String,
+}
+
+#[derive(Deserialize)]
+struct ScryptParams {
+    dklen: usize,
+    n: u64,
+    r: u32,
+    p: u32,
+    salt: String,
+}
+
+#[derive(Deserialize)]
+struct Pbkdf2Params {
+    dklen: usize,
+    c: u32,
+    prf: String,
+    salt: String,
+}
+
+fn decrypt_keystore(raw: &str, password: &[u8]) -> Result<SecretKey> {
+    let crypto = serde_json::from_str::<Keystore>(raw).context("Not a JSON keystore")?.crypto;
+    if crypto.cipher != "aes-128-ctr" {
+        anyhow::bail!("Unsupported keystore cipher {}", crypto.cipher);
+    }
+    let derived = match crypto.kdf.as_str() {
+        "scrypt" => {
+            let params: ScryptParams = serde_json::from_value(crypto.kdfparams).context("Invalid scrypt params")?;
+            scrypt(password, &utils::parse_hex(&params.salt)?, params.n, params.r, params.p, params.dklen)?
+        }
+        "pbkdf2" => {
+            let params: Pbkdf2Params = serde_json::from_value(crypto.kdfparams).context("Invalid pbkdf2 params")?;
+            if params.prf != "hmac-sha256" {
+                anyhow::bail!("Unsupported pbkdf2 prf {}", params.prf);
+            }
+            let mut derived = vec![0u8; params.dklen];
+            pbkdf2::pbkdf2::<Hmac<Sha256>>(password, &utils::parse_hex(&params.salt)?, params.c, &mut derived);
+            derived
+        }
+        kdf => anyhow::bail!("Unsupported keystore kdf {}", kdf),
+    };
+    if derived.len() < 32 {
+        anyhow::bail!("Keystore dklen {} is too short", derived.len());
+    }
+
+    let mut key = utils::parse_hex(&crypto.ciphertext)?;
+    let mac = keccak::hashv(&[&derived[16..32], &key]);
+    if mac.as_ref() != utils::parse_hex(&crypto.mac)?.as_slice() {
+        anyhow::bail!("Wrong password (MAC mismatch)");
+    }
+    let iv = utils::parse_hex(&crypto.cipherparams.iv)?;
+    let mut cipher = ctr::Ctr128BE::<Aes128>::new_from_slices(&derived[..16], &iv)
+        .map_err(|_| anyhow::anyhow!("Keystore IV must be 16 bytes"))?;
+    cipher.apply_keystream(&mut key);
+    SecretKey::parse_slice(&key).map_err(|e| anyhow::anyhow!("Keystore holds an invalid private key: {:?}", e))
+}
+
+/// scrypt as geth and ethers compute it: RFC 7914 without its `n < 2^(16 r)` bound, which the
+/// keystore spec's own test vector (n = 2^18, r = 1) breaks.
+fn scrypt(password: &[u8], salt: &[u8], n: u64, r: u32, p: u32, dklen: usize) -> Result<Vec<u8>> {
+    if n < 2 || !n.is_power_of_two() || r == 0 || p == 0 {
+        anyhow::bail!("Invalid scrypt params n={} r={} p={}", n, r, p);
+    }
+    let block_len = 128 * r as usize;
+    let fits = |blocks: u64| usize::try_from(blocks).ok().and_then(|b| b.checked_mul(block_len)).is_some_and(|m| m <= MAX_SCRYPT_MEMORY);
+    if !fits(n) || !fits(p.into()) {
+        anyhow::bail!("Unsupported scrypt params n={} r={} p={} (over {} MiB)", n, r, p, MAX_SCRYPT_MEMORY >> 20);
+    }
+    let mut blocks = vec![0u8; p as usize * block_len];
+    pbkdf2::pbkdf2::<Hmac<Sha256>>(password, salt, 1, &mut blocks);
+    for block in blocks.chunks_exact_mut(block_len) {
+        ro_mix(block, n as usize);
+    }
+    let mut derived = vec![0u8; dklen];
+    pbkdf2::pbkdf2::<Hmac<Sha256>>(password, &blocks, 1, &mut derived);
+    Ok(derived)
+}
+
+/// Largest scrypt working memory a keystore may ask for; geth's standard params take 256 MiB.
+const MAX_SCRYPT_MEMORY: usize = 1 << 30;
+
+/// scryptROMix of one `128 r`-byte block.
+fn ro_mix(block: &mut [u8], n: usize) {
+    let words = block.len() / 4;
+    let mut x: Vec<u32> = block.chunks_exact(4).map(|w| u32::from_le_bytes(w.try_into().expect("4 bytes"))).collect();
+    let mut y = vec![0u32; words];
+    let mut v = vec![0u32; n * words];
+    for i in 0..n {
+        v[i * words..(i + 1) * words].copy_from_slice(&x);
+        block_mix(&x, &mut y);
+        std::mem::swap(&mut x, &mut y);
+    }
+    for _ in 0..n {
+        // Integerify: the first word of the last 64-byte chunk, mod n
+        let j = x[words - 16] as usize & (n - 1);
+        for (xi, vi) in x.iter_mut().zip(&v[j * words..(j + 1) * words]) {
+            *xi ^= vi;
+        }
+        block_mix(&x, &mut y);
+        std::mem::swap(&mut x, &mut y);
+    }
+    for (chunk, word) in block.chunks_exact_mut(4).zip(x) {
+        chunk.copy_from_slice(&word.to_le_bytes());
+    }
+}
+
+/// scryptBlockMix: Salsa20/8 over the 64-byte chunks, even outputs first, then odd ones.
+fn block_mix(input: &[u32], output: &mut [u32]) {
+    let chunks = input.len() / 16;
+    let mut x: [u32; 16] = input[input.len() - 16..].try_into().expect("16 words");
+    for (i, chunk) in input.chunks_exact(16).enumerate() {
+        for (xk, ck) in x.iter_mut().zip(chunk) {
+            *xk ^= ck;
+        }
+        salsa20_8(&mut x);
+        let slot = if i % 2 == 0 { i / 2 } else { chunks / 2 + i / 2 };
+        output[slot * 16..(slot + 1) * 16].copy_from_slice(&x);
+    }
+}
+
+fn salsa20_8(b: &mut [u32; 16]) {
+    fn quarter(x: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+        x[b] ^= x[a].wrapping_add(x[d]).rotate_left(7);
+        x[c] ^= x[b].wrapping_add(x[a]).rotate_left(9);
+        x[d] ^= x[c].wrapping_add(x[b]).rotate_left(13);
+        x[a] ^= x[d].wrapping_add(x[c]).rotate_left(18);
+    }
+    let mut x = *b;
+    for _ in 0..4 {
+        // Columns, then rows
+        quarter(&mut x, 0, 4, 8, 12);
+        quarter(&mut x, 5, 9, 13, 1);
+        quarter(&mut x, 10, 14, 2, 6);
+        quarter(&mut x, 15, 3, 7, 11);
+        quarter(&mut x, 0, 1, 2, 3);
+        quarter(&mut x, 5, 6, 7, 4);
+        quarter(&mut x, 10, 11, 8, 9);
+        quarter(&mut x, 15, 12, 13, 14);
+    }
+    for (bi, xi) in b.iter_mut().zip(x) {
+        *bi = bi.wrapping_add(xi);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Private key of the Web3 Secret Storage v3 test vectors, encrypted with "testpassword".
+    const TEST_KEY: &str = "7a28b5ba57c53603b0b07b56bba752f7784bf506fa95edc395f5cf6c7514fe9d";
+
+    /// The spec's pbkdf2 test vector.
+    const PBKDF2_KEYSTORE: &str = r#"{
+        "crypto": {
+            "cipher": "aes-128-ctr",
+            "cipherparams": { "iv": "6087dab2f9fdbbfaddc31a909735c1e6" },
+            "ciphertext": "5318b4d5bcd28de64ee5559e671353e16f075ecae9f99c7a79a38af5f869aa46",
+            "kdf": "pbkdf2",
+            "kdfparams": {
+                "c": 262144,
+                "dklen": 32,
+                "prf": "hmac-sha256",
+                "salt": "ae3cd4e7013836a3df6bd7241b12db061dbe2c6785853cce422d148a624ce0bd"
+            },
+            "mac": "517ead924a9d0dc3124507e3393d175ce3ff7c1e96529c6c555ce9e51205e9b2"
+        },
+        "id": "3198bc9c-6672-5ab3-d995-4942343ae5b6",
+        "version": 3
+    }"#;
+
+    /// The spec's scrypt test vector. Its `r = 1` with `n = 2^18` breaks RFC 7914's
+    /// `n < 2^(16 r)`, which geth does not enforce.
+    const SCRYPT_SPEC_KEYSTORE: &str = r#"{
+        "crypto": {
+            "cipher": "aes-128-ctr",
+            "cipherparams": { "iv": "83dbcc02d8ccb40e466191a123791e0e" },
+            "ciphertext": "d172bf743a674da9cdad04534d56926ef8358534d458fffccd4e6ad2fbde479c",
+            "kdf": "scrypt",
+            "kdfparams": {
+                "dklen": 32,
+                "n": 262144,
+                "p": 8,
+                "r": 1,
+                "salt": "ab0c7876052600dd703518d6fc3fe8984592145b591fc8fb5c6d43190334ba19"
+            },
+            "mac": "2103ac29920d71da29f15d75b4a16dbe95cfd7ff8faea1056c33131d846e3097"
+        },
+        "id": "3198bc9c-6672-5ab3-d995-4942343ae5b6",
+        "version": 3
+    }"#;
+
+    /// The same key under geth's standard scrypt params (n = 2^18, r = 8, p = 1), encrypted with
+    /// OpenSSL's scrypt and AES-128-CTR.
+    const GETH_STANDARD_KEYSTORE: &str = r#"{
+        "crypto": {
+            "cipher": "aes-128-ctr",
+            "cipherparams": { "iv": "303132333435363738393a3b3c3d3e3f" },
+            "ciphertext": "3ba6cb447374db6cdc29124a8ea3543d7fca5e72f26d62eb8b5130679fc3cd6d",
+            "kdf": "scrypt",
+            "kdfparams": {
+                "dklen": 32,
+                "n": 262144,
+                "p": 1,
+                "r": 8,
+                "salt": "202122232425262728292a2b2c2d2e2f303132333435363738393a3b3c3d3e3f"
+            },
+            "mac": "5cd242eb5fb1530838bdbd2a982adf2c78b467a6642734e323cacd7f1c23c3c4"
+        },
+        "version": 3
+    }"#;
+
+    /// The same key under geth's light scrypt params (n = 4096, r = 8, p = 6).
+    const SCRYPT_KEYSTORE: &str = r#"{
+        "crypto": {
+            "cipher": "aes-128-ctr",
+            "cipherparams": { "iv": "101112131415161718191a1b1c1d1e1f" },
+            "ciphertext": "b2743a23558bc5334dfed25f04f01c1243137eab728f995a5d662f7f5f1b2863",
+            "kdf": "scrypt",
+            "kdfparams": {
+                "dklen": 32,
+                "n": 4096,
+                "p": 6,
+                "r": 8,
+                "salt": "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f"
+            },
+            "mac": "d6d043babd63b66687a50ab7de44b1a12c61e084e2ce615250b1a67756c1eca0"
+        },
+        "version": 3
+    }"#;
+
+    #[test]
+    fn decrypts_pbkdf2_test_vector() {
+        let key = decrypt_keystore(PBKDF2_KEYSTORE, b"testpassword").unwrap();
+        assert_eq!(hex::encode(key.serialize()), TEST_KEY);
+    }
+
+    #[test]
+    fn decrypts_scrypt_keystore() {
+        let key = decrypt_keystore(SCRYPT_KEYSTORE, b"testpassword").unwrap();
+        assert_eq!(hex::encode(key.serialize()), TEST_KEY);
+    }
+
+    #[test]
+    fn decrypts_scrypt_test_vector() {
+        let key = decrypt_keystore(SCRYPT_SPEC_KEYSTORE, b"testpassword").unwrap();
+        assert_eq!(hex::encode(key.serialize()), TEST_KEY);
+    }
+
+    #[test]
+    fn decrypts_geth_standard_scrypt_keystore() {
+        let key = decrypt_keystore(GETH_STANDARD_KEYSTORE, b"testpassword").unwrap();
+        assert_eq!(hex::encode(key.serialize()), TEST_KEY);
+    }
+
+    #[test]
+    fn matches_rfc_7914_scrypt_vector() {
+        let derived = scrypt(b"password", b"NaCl", 1024, 8, 16, 64).unwrap();
+        assert_eq!(
+            hex::encode(derived),
+            "fdbabe1c9d3472007856e7190d01e9fe7c6ad7cbc8237830e77376634b3731622eaf30d92e22a3886ff109279d9830dac727afb94a83ee6d8360cbdfa2cc0640"
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_scrypt_params() {
+        assert!(scrypt(b"", b"", 1000, 8, 1, 32).unwrap_err().to_string().contains("n=1000 r=8 p=1"));
+        assert!(scrypt(b"", b"", 1024, 0, 1, 32).is_err());
+        let err = scrypt(b"", b"", 1 << 30, 8, 1, 32).unwrap_err();
+        assert!(err.to_string().contains("over 1024 MiB"), "{}", err);
+    }
+
+    #[test]
+    fn rejects_wrong_password() {
+        let err = decrypt_keystore(SCRYPT_KEYSTORE, b"wrongpassword").unwrap_err();
+        assert!(err.to_string().contains("MAC mismatch"), "{}", err);
+    }
+}
@@ -0,0 +1,330 @@
+use clap::Subcommand;
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+use anyhow::{Result, Context};
+use crate::deploy::SOLANA_CHAIN;
+use crate::export::{self, Check, Exporter, PeerField, StoreField, Submit};
+use crate::network::SolanaProfile;
+use crate::signer::KeySource;
+use crate::oft_client::{self, OFTStore, OftClient, PeerConfig, PeerConfigParam, QuoteParams, RateLimitParams, RateLimiter, SendParams, SetOFTConfigParams, SetPeerConfigParams};
+use crate::{options, utils};
+
+/// Direct calls into an existing Solana OFT store.
+#[derive(Subcommand)]
+pub enum SolanaAction {
+    /// Print the OFT store and, for each `--eid`, its peer config
+    Show {
+        #[arg(long)]
+        eid: Vec<u32>,
+    },
+    /// `set_oft_config`: one instruction per flag given, sent in a single transaction
+    SetConfig {
+        #[arg(long)]
+        admin: Option<Pubkey>,
+        /// OApp delegate registered with the endpoint
+        #[arg(long)]
+        delegate: Option<Pubkey>,
+        #[arg(long)]
+        default_fee_bps: Option<u16>,
+        /// Set the paused flag directly as admin
+        #[arg(long)]
+        paused: Option<bool>,
+        /// Pubkey, or `none` to remove
+        #[arg(long)]
+        pauser: Option<String>,
+        /// Pubkey, or `none` to remove
+        #[arg(long)]
+        unpauser: Option<String>,
+    },
+    /// `set_peer_config` for one remote EID: one instruction per flag given
+    SetPeer {
+        #[arg(long)]
+        eid: u32,
+        /// Remote OFT: EVM address, 32-byte hex or base58 pubkey
+        #[arg(long)]
+        address: Option<String>,
+        /// Fee override in bps, or `none` to fall back to the default fee
+        #[arg(long)]
+        fee_bps: Option<String>,
+        /// Enforced type-3 options for `send`, hex
+        #[arg(long, requires = "enforced_send_and_call")]
+        enforced_send: Option<String>,
+        /// Enforced type-3 options for `send` with a compose message, hex
+        #[arg(long, requires = "enforced_send")]
+        enforced_send_and_call: Option<String>,
+        #[arg(long)]
+        outbound_capacity: Option<u64>,
+        #[arg(long)]
+        outbound_refill_per_second: Option<u64>,
+        #[arg(long, conflicts_with_all = ["outbound_capacity", "outbound_refill_per_second"])]
+        clear_outbound: bool,
+        #[arg(long)]
+        inbound_capacity: Option<u64>,
+        #[arg(long)]
+        inbound_refill_per_second: Option<u64>,
+        #[arg(long, conflicts_with_all = ["inbound_capacity", "inbound_refill_per_second"])]
+        clear_inbound: bool,
+    },
+    /// `set_pause(true)`, signed by the pauser
+    Pause,
+    /// `set_pause(false)`, signed by the unpauser
+    Unpause,
+    /// `withdraw_fee` from the escrow
+    WithdrawFee {
+        /// Amount in local decimals
+        #[arg(long)]
+        amount: u64,
+        /// Destination token account
+        #[arg(long)]
+        dest: Pubkey,
+    },
+    /// `quote_oft` and `quote_send`
+    Quote(TransferArgs),
+    /// `send`, paying the quoted native fee
+    Send {
+        #[command(flatten)]
+        transfer: TransferArgs,
+        /// Token account to send from
+        #[arg(long)]
+        token_source: Pubkey,
+    },
+}
+
+#[derive(clap::Args)]
+pub struct TransferArgs {
+    #[arg(long)]
+    dst_eid: u32,
+    /// Recipient: EVM address, 32-byte hex or base58 pubkey
+    #[arg(long)]
+    to: String,
+    /// Amount in local decimals
+    #[arg(long)]
+    amount: u64,
+    #[arg(long, default_value_t = 0)]
+    min_amount: u64,
+    /// Extra type-3 options, hex (see `oft-cli options build`)
+    #[arg(long, default_value = "")]
+    options: String,
+}
+
+impl TransferArgs {
+    fn quote_params(&self) -> Result<QuoteParams> {
+        Ok(QuoteParams {
+            dst_eid: self.dst_eid,
+            to: utils::parse_bytes32(&self.to)?,
+            amount_ld: self.amount,
+            min_amount_ld: self.min_amount,
+            options: match self.options.as_str() {
+                "" => Vec::new(),
+                hex => options::parse_type_3(hex)?,
+            },
+            compose_msg: None,
+            pay_in_lz_token: false,
+        })
+    }
+}
+
+/// Exports go to the authority the action needs: the pauser/unpauser for `pause`/`unpause`,
+/// otherwise the admin.
+fn export_authority(client: &OftClient, oft_store: &Pubkey, action: &SolanaAction) -> Result<Pubkey> {
+    let store = client.oft_store(oft_store)?;
+    match action {
+        SolanaAction::Pause => store.pauser.context("The OFT store has no pauser"),
+        SolanaAction::Unpause => store.unpauser.context("The OFT store has no unpauser"),
+        SolanaAction::Quote(_) | SolanaAction::Send { .. } => anyhow::bail!("Only admin actions can be exported"),
+        _ => Ok(store.admin),
+    }
+}
+
+pub async fn run(profile: &SolanaProfile, key: &KeySource, oft_store: &Pubkey, action: &SolanaAction, exporter: &mut Option<Exporter>) -> Result<()> {
+    let client = OftClient::new(profile)?;
+    let mut submit = Submit::new(exporter, || Ok(key.clone()))?;
+    let me = match action {
+        SolanaAction::Show { .. } => Pubkey::default(),
+        _ => submit.solana_authority(|| export_authority(&client, oft_store, action))?,
+    };
+    let mut checks = Vec::new();
+
+    let instructions: Vec<Instruction> = match action {
+        SolanaAction::Show { eid } => {
+            let store = client.oft_store(oft_store)?;
+            print_store(oft_store, &client.program_id, &store);
+            for eid in eid {
+                println!("\n🔗 Peer EID {}", eid);
+                println!("   peer PDA            {}", oft_client::peer_pda(&client.program_id, oft_store, *eid));
+                println!("   enforced opts PDA   {}", oft_client::enforced_options_pda(&client.program_id, oft_store, *eid));
+                match client.peer_config(oft_store, *eid)? {
+                    Some(peer) => print_peer(&peer),
+                    None => println!("   not set"),
+                }
+            }
+            return Ok(());
+        }
+        SolanaAction::SetConfig { admin, delegate, default_fee_bps, paused, pauser, unpauser } => {
+            let mut params = Vec::new();
+            // Admin goes last: every change before it is signed by the current admin
+            params.extend(delegate.map(SetOFTConfigParams::Delegate));
+            params.extend(default_fee_bps.map(SetOFTConfigParams::DefaultFee));
+            params.extend(paused.map(SetOFTConfigParams::Paused));
+            if let Some(pauser) = pauser {
+                params.push(SetOFTConfigParams::Pauser(parse_optional_pubkey(pauser)?));
+            }
+            if let Some(unpauser) = unpauser {
+                params.push(SetOFTConfigParams::Unpauser(parse_optional_pubkey(unpauser)?));
+            }
+            params.extend(admin.map(SetOFTConfigParams::Admin));
+            for param in &params {
+                checks.push(match param {
+                    SetOFTConfigParams::Admin(key) => Check::store(oft_store, StoreField::Admin, key),
+                    SetOFTConfigParams::Delegate(key) => Check::store(oft_store, StoreField::Delegate, key),
+                    SetOFTConfigParams::DefaultFee(bps) => Check::store(oft_store, StoreField::DefaultFeeBps, bps),
+                    SetOFTConfigParams::Paused(paused) => Check::store(oft_store, StoreField::Paused, paused),
+                    SetOFTConfigParams::Pauser(key) => Check::store(oft_store, StoreField::Pauser, export::fmt_key(*key)),
+                    SetOFTConfigParams::Unpauser(key) => Check::store(oft_store, StoreField::Unpauser, export::fmt_key(*key)),
+                });
+            }
+            params
+                .into_iter()
+                .map(|p| client.set_oft_config_ix(&me, oft_store, p))
+                .collect::<Result<_>>()?
+        }
+        SolanaAction::SetPeer {
+            eid, address, fee_bps, enforced_send, enforced_send_and_call,
+            outbound_capacity, outbound_refill_per_second, clear_outbound,
+            inbound_capacity, inbound_refill_per_second, clear_inbound,
+        } => {
+            let mut configs = Vec::new();
+            if let Some(address) = address {
+                let address = utils::parse_bytes32(address)?;
+                checks.push(Check::peer(oft_store, *eid, PeerField::PeerAddress, format!("0x{}", hex::encode(address))));
+                configs.push(PeerConfigParam::PeerAddress(address));
+            }
+            if let Some(fee_bps) = fee_bps {
+                let bps = match fee_bps.as_str() {
+                    "none" => None,
+                    bps => Some(bps.parse().with_context(|| format!("Invalid fee bps: {}", bps))?),
+                };
+                checks.push(Check::peer(oft_store, *eid, PeerField::FeeBps, export::fmt_fee_bps(bps)));
+                configs.push(PeerConfigParam::FeeBps(bps));
+            }
+            if let (Some(send), Some(send_and_call)) = (enforced_send, enforced_send_and_call) {
+                let (send, send_and_call) = (options::parse_type_3(send)?, options::parse_type_3(send_and_call)?);
+                checks.push(Check::peer(oft_store, *eid, PeerField::EnforcedOptions, export::fmt_enforced_options(&send, &send_and_call)));
+                configs.push(PeerConfigParam::EnforcedOptions { send, send_and_call });
+            }
+            if *clear_outbound {
+                configs.push(PeerConfigParam::OutboundRateLimit(None));
+            } else if outbound_capacity.is_some() || outbound_refill_per_second.is_some() {
+                configs.push(PeerConfigParam::OutboundRateLimit(Some(RateLimitParams {
+                    refill_per_second: *outbound_refill_per_second,
+                    capacity: *outbound_capacity,
+                })));
+            }
+            if *clear_inbound {
+                configs.push(PeerConfigParam::InboundRateLimit(None));
+            } else if inbound_capacity.is_some() || inbound_refill_per_second.is_some() {
+                configs.push(PeerConfigParam::InboundRateLimit(Some(RateLimitParams {
+                    refill_per_second: *inbound_refill_per_second,
+                    capacity: *inbound_capacity,
+                })));
+            }
+            configs
+                .into_iter()
+                .map(|config| client.set_peer_config_ix(&me, oft_store, SetPeerConfigParams { remote_eid: *eid, config }))
+                .collect::<Result<_>>()?
+        }
+        SolanaAction::Pause | SolanaAction::Unpause => {
+            let paused = matches!(action, SolanaAction::Pause);
+            checks.push(Check::store(oft_store, StoreField::Paused, paused));
+            vec![client.set_pause_ix(&me, oft_store, paused)?]
+        }
+        SolanaAction::WithdrawFee { amount, dest } => vec![client.withdraw_fee_ix(&me, oft_store, dest, *amount)?],
+        SolanaAction::Quote(transfer) => {
+            let params = transfer.quote_params()?;
+            let quote = client.quote_oft(&me, oft_store, &params)?;
+            println!("📊 Limits: {} - {}", quote.oft_limits.min_amount_ld, quote.oft_limits.max_amount_ld);
+            for fee in &quote.oft_fee_details {
+                println!("   fee {} ({})", fee.fee_amount_ld, fee.description);
+            }
+            println!("   sent {}, received {}", quote.oft_receipt.amount_sent_ld, quote.oft_receipt.amount_received_ld);
+            let fee = client.quote_send(&me, oft_store, &params)?;
+            println!("💸 quote_send: native fee {} lamports, LZ token fee {}", fee.native_fee, fee.lz_token_fee);
+            return Ok(());
+        }
+        SolanaAction::Send { transfer, token_source } => {
+            let quote = transfer.quote_params()?;
+            let store = client.oft_store(oft_store)?;
+            let dust = quote.amount_ld - store.remove_dust(quote.amount_ld);
+            if dust > 0 {
+                println!("⚠️  {} of the amount is below shared-decimals precision and stays with the sender", dust);
+            }
+            let fee = client.quote_send(&me, oft_store, &quote)?;
+            println!("💸 Native fee: {} lamports", fee.native_fee);
+            let params = SendParams {
+                dst_eid: quote.dst_eid,
+                to: quote.to,
+                amount_ld: quote.amount_ld,
+                min_amount_ld: quote.min_amount_ld,
+                options: quote.options,
+                compose_msg: quote.compose_msg,
+                native_fee: fee.native_fee,
+                lz_token_fee: 0,
+            };
+            vec![client.send_ix(&me, oft_store, token_source, &params)?]
+        }
+    };
+
+    if instructions.is_empty() {
+        anyhow::bail!("Nothing to do: pass at least one setting");
+    }
+    if let Some(sig) = submit.solana(&client, SOLANA_CHAIN, &me, &instructions, "solana", checks)? {
+        println!("✅ Tx: {}", sig);
+    }
+    Ok(())
+}
+
+fn parse_optional_pubkey(value: &str) -> Result<Option<Pubkey>> {
+    match value {
+        "none" => Ok(None),
+        key => Ok(Some(key.parse().with_context(|| format!("Invalid pubkey: {}", key))?)),
+    }
+}
+
+fn print_store(address: &Pubkey, program_id: &Pubkey, store: &OFTStore) {
+    println!("📦 OFT store {} (program {})", address, program_id);
+    println!("   type                {:?}", store.oft_type);
+    println!("   token mint          {}", store.token_mint);
+    println!("   token escrow        {}", store.token_escrow);
+    println!("   endpoint            {}", store.endpoint_program);
+    println!("   ld2sd rate          {}", store.ld2sd_rate);
+    println!("   tvl (ld)            {}", store.tvl_ld);
+    println!("   admin               {}", store.admin);
+    println!("   default fee (bps)   {}", store.default_fee_bps);
+    println!("   paused              {}", store.paused);
+    println!("   pauser              {}", fmt_optional(&store.pauser));
+    println!("   unpauser            {}", fmt_optional(&store.unpauser));
+    println!("   bump                {}", store.bump);
+}
+
+fn print_peer(peer: &PeerConfig) {
+    println!("   address             0x{}", hex::encode(peer.peer_address));
+    println!("   fee (bps)           {}", peer.fee_bps.map_or("default".to_string(), |b| b.to_string()));
+    println!("   enforced send       0x{} ({})", hex::encode(&peer.enforced_options.send), options::describe(&peer.enforced_options.send));
+    println!("   enforced send+call  0x{} ({})", hex::encode(&peer.enforced_options.send_and_call), options::describe(&peer.enforced_options.send_and_call));
+    println!("   outbound limit      {}", fmt_limiter(&peer.outbound_rate_limiter));
+    println!("   inbound limit       {}", fmt_limiter(&peer.inbound_rate_limiter));
+    println!("   bump                {}", peer.bump);
+}
+
+fn fmt_optional(key: &Option<Pubkey>) -> String {
+    key.map_or("none".to_string(), |k| k.to_string())
+}
+
+fn fmt_limiter(limiter: &Option<RateLimiter>) -> String {
+    match limiter {
+        None => "none".to_string(),
+        Some(l) => format!(
+            "{} / {} tokens, +{}/s, last refill {}",
+            l.tokens, l.capacity, l.refill_per_second, l.last_refill_time
+        ),
+    }
+}
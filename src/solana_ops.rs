@@ -1,236 +1,349 @@
 use solana_sdk::{
-    signature::{read_keypair_file, Keypair, Signer},
+    compute_budget::ComputeBudgetInstruction,
+    signature::{read_keypair_file, Keypair, Signature, Signer},
     pubkey::Pubkey,
-    system_program,
     transaction::Transaction,
-    instruction::{AccountMeta, Instruction},
-    commitment_config::CommitmentConfig,
+    instruction::Instruction,
+    message::Message,
 };
-use sha2::{Digest, Sha256};
-use solana_client::rpc_client::RpcClient;
-use borsh::BorshSerialize;
+use solana_client::{rpc_client::RpcClient, rpc_config::RpcSimulateTransactionConfig};
 use std::str::FromStr;
 use anyhow::{Result, Context};
+use crate::export::{self, Check, PeerField, Submit};
+use crate::network::SolanaProfile;
+use crate::options::{self, EnforcedOptionsSpec};
+use crate::oft_client::{self, InitOFTParams, OFTType, OftClient, PeerConfigParam, SetPeerConfigParams};
+use crate::preflight::{self, Decimals};
+use crate::rate_limit::{self, PeerRateLimits, RateLimitSpec};
+use crate::signer::KeySource;
+use crate::spl;
+use crate::state::DeploymentState;
 
-// ⚠️ PASTE THE ID FROM YOUR SCRIPT OUTPUT HERE ⚠️
-const LZ_PROGRAM_ID: &str = "DQTTjSLNrNU97djqffEeRKPFD8idj12CiUeXfEg7AHbp"; 
+pub const DEFAULT_KEYPAIR_PATH: &str = "~/.config/solana/id.json";
 
-#[derive(BorshSerialize)]
-struct InitAdapterArgs {
-    shared_decimals: u8,
+/// Shared decimals of the OFT; matches the EVM OFT default.
+pub const DEFAULT_SHARED_DECIMALS: u8 = 6;
+
+/// Compute units for creating the mint, its metadata and the OFT store in one transaction.
+const CREATE_MINT_COMPUTE_UNITS: u32 = 400_000;
+
+/// Mint backing the Solana OFT: an existing one, or a new one created by the deployment.
+pub enum MintSource {
+    Existing(String),
+    Create(MintSpec),
 }
 
-#[derive(BorshSerialize)]
-struct SetPeerArgs {
-    dst_eid: u32,
-    peer_address: [u8; 32],
+/// Token created as part of the deployment.
+pub struct MintSpec {
+    pub decimals: u8,
+    pub token_2022: bool,
+    /// Metaplex metadata, if any.
+    pub metadata: Option<TokenMetadata>,
 }
 
-pub async fn init_adapter(mint_str: &str) -> Result<Pubkey> {
-    println!("🛠️  Initializing Solana OFT Adapter (Auto) for Mint: {}", mint_str);
+pub struct TokenMetadata {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+}
 
-    let rpc_url = "https://api.devnet.solana.com";
-    let client = RpcClient::new_with_commitment(rpc_url.to_string(), CommitmentConfig::confirmed());
-    let payer_path = shellexpand::tilde("~/.config/solana/id.json");
-    
-    // FIX 1: Map error for anyhow
-    let payer = read_keypair_file(payer_path.as_ref())
-        .map_err(|e| anyhow::anyhow!(e.to_string()))
-        .context("Failed to find Solana wallet")?;
-
-    let program_id = Pubkey::from_str(LZ_PROGRAM_ID)?;
-    let mint = Pubkey::from_str(mint_str)?;
-
-    // SEED: LZAutoV1 (Matches the script)
-    let (oft_config_pda, _bump) = Pubkey::find_program_address(
-        &[b"LZAutoV1", mint.as_ref()], 
-        &program_id
-    );
-    println!("📍 Calculated PDA: {}", oft_config_pda);
-
-    // Compute discriminator for "init_adapter" dynamically
-    let mut hasher = Sha256::new();
-    hasher.update("global:init_adapter");
-    let hash = hasher.finalize();
-    let mut discriminator: [u8; 8] = [0u8; 8];
-    discriminator.copy_from_slice(&hash[0..8]);
-    println!("🔧 Using discriminator for 'init_adapter': {}", hex::encode(&discriminator));
-    let args = InitAdapterArgs { shared_decimals: 6 };
-    
-    let mut data = Vec::new();
-    data.extend_from_slice(&discriminator);
-    args.serialize(&mut data)?;
-
-    let accounts = vec![
-        AccountMeta::new(oft_config_pda, false),
-        AccountMeta::new_readonly(mint, false), 
-        AccountMeta::new(payer.pubkey(), true), 
-        AccountMeta::new_readonly(system_program::id(), false),
-    ];
+/// The Solana OFT to deploy: its mint and whether the store mints/burns or locks/unlocks it.
+pub struct SolanaOftSpec {
+    pub mint: MintSource,
+    pub oft_type: OFTType,
+}
 
-    let instruction = Instruction::new_with_bytes(program_id, &data, accounts);
+impl SolanaOftSpec {
+    /// Native for a created mint, adapter for an existing one, unless `oft_type` says otherwise.
+    pub fn new(mint: MintSource, oft_type: Option<OFTType>) -> Self {
+        let oft_type = oft_type.unwrap_or(match mint {
+            MintSource::Existing(_) => OFTType::Adapter,
+            MintSource::Create(_) => OFTType::Native,
+        });
+        SolanaOftSpec { mint, oft_type }
+    }
 
-    let recent_blockhash = client.get_latest_blockhash()?;
-    let transaction = Transaction::new_signed_with_payer(
-        &[instruction],
-        Some(&payer.pubkey()),
-        &[&payer],
-        recent_blockhash,
-    );
+    /// OFT store already recorded for `chain`. An existing mint must match the recorded one;
+    /// a created mint is whatever the earlier run recorded, as long as it was created the same way.
+    pub fn recorded_pda(&self, state: &DeploymentState, chain: &str) -> Result<Option<String>> {
+        state.check_spec(chain, &self.spec())?;
+        match &self.mint {
+            MintSource::Existing(mint) => state.solana_pda(chain, mint),
+            MintSource::Create(_) => Ok(state.solana_oft(chain).map(|(_, pda)| pda)),
+        }
+    }
 
-    println!("🚀 Sending Init Transaction...");
-    match client.send_and_confirm_transaction(&transaction) {
-        Ok(sig) => println!("✅ Solana Adapter Initialized! Tx: {}", sig),
-        Err(e) => {
-            println!("⚠️  Init transaction failed: {}", e);
-            println!("🔍 Simulating init transaction to fetch program logs...");
-            match client.simulate_transaction(&transaction) {
-                Ok(sim) => {
-                    if let Some(logs) = sim.value.logs {
-                        println!("📜 Init program logs:");
-                        for l in logs { println!("{}", l); }
-                    } else { println!("📜 No logs returned in simulation."); }
-                    if let Some(err) = sim.value.err {
-                        println!("Simulated init instruction error: {:?}", err);
-                    }
-                },
-                Err(se) => println!("Simulation RPC error: {}", se),
+    /// Recorded in the state so a rerun with other settings does not reuse this store.
+    pub fn spec(&self) -> String {
+        let mint = match &self.mint {
+            MintSource::Existing(mint) => format!("mint {}", mint),
+            MintSource::Create(spec) => {
+                let program = if spec.token_2022 { "Token-2022" } else { "SPL Token" };
+                let metadata = match &spec.metadata {
+                    Some(m) => format!(", metadata {:?} {:?} {:?}", m.name, m.symbol, m.uri),
+                    None => String::new(),
+                };
+                format!("created {} mint with {} decimals{}", program, spec.decimals, metadata)
             }
-        }
+        };
+        format!("{} over {}", oft_type_name(self.oft_type), mint)
+    }
+
+    /// `init_oft`, preceded by the mint creation when there is one.
+    pub fn action(&self) -> String {
+        let create = if let MintSource::Create(_) = self.mint { "create mint + " } else { "" };
+        format!("{}init_oft ({})", create, oft_type_name(self.oft_type))
     }
+}
 
-    Ok(oft_config_pda)
+fn oft_type_name(oft_type: OFTType) -> &'static str {
+    match oft_type {
+        OFTType::Native => "native",
+        OFTType::Adapter => "adapter",
+    }
 }
 
-pub async fn set_peer_solana(oft_config: Pubkey, target_eid: u32, peer_address: [u8; 32]) -> Result<()> {
-    println!("🔗 Wiring Solana -> EVM (EID: {})...", target_eid);
+/// Outcome of simulating an instruction without signing or broadcasting it.
+pub struct Simulation {
+    pub err: Option<String>,
+    pub logs: Vec<String>,
+}
 
-    let rpc_url = "https://api.devnet.solana.com";
-    let client = RpcClient::new_with_commitment(rpc_url.to_string(), CommitmentConfig::confirmed());
-    let payer_path = shellexpand::tilde("~/.config/solana/id.json");
-    
-    // FIX 2: Map error for anyhow (This was the missing piece!)
-    let payer = read_keypair_file(payer_path.as_ref())
+pub fn load_keypair(payer_path: &str) -> Result<Keypair> {
+    let payer_path = shellexpand::tilde(payer_path);
+    read_keypair_file(payer_path.as_ref())
         .map_err(|e| anyhow::anyhow!(e.to_string()))
-        .context("Failed to read keypair")?;
-
-    let program_id = Pubkey::from_str(LZ_PROGRAM_ID)?;
-
-    // Compute discriminator for "wire_evm" dynamically (first 8 bytes of sha256('global:wire_evm'))
-    let mut hasher = Sha256::new();
-    hasher.update("global:wire_evm");
-    let hash = hasher.finalize();
-    let mut discriminator: [u8; 8] = [0u8; 8];
-    discriminator.copy_from_slice(&hash[0..8]);
-    println!("🔧 Using discriminator for 'wire_evm': {}", hex::encode(&discriminator)); 
-
-    // Diagnostic: try candidate Anchor discriminators and simulate them so we can find a match without sending on-chain txs
-    let candidates = [
-        "wire_evm",
-        "set_peer",
-        "set_peer_solana",
-        "set_peer_v1",
-        "set_peer_with_peer",
-        "initialize",
-        "init_adapter",
-        "set_peer_evm",
-    ];
-    println!("🔎 Anchor discriminator hints (name -> first 8 bytes of sha256('global:<name>')) and simulation result:");
+        .context("Failed to find Solana wallet")
+}
+
+pub fn account_exists(profile: &SolanaProfile, address: &Pubkey) -> Result<bool> {
+    let client = RpcClient::new_with_commitment(profile.rpc_url.clone(), profile.commitment_config());
+    Ok(client.get_account_with_commitment(address, profile.commitment_config())?.value.is_some())
+}
 
-    let args = SetPeerArgs {
-        dst_eid: target_eid,
-        peer_address: peer_address,
+/// Simulates `instructions` as one transaction with signature verification off, so no key material is needed.
+pub fn simulate_ixs(profile: &SolanaProfile, payer: &Pubkey, instructions: &[Instruction]) -> Result<Simulation> {
+    let client = RpcClient::new_with_commitment(profile.rpc_url.clone(), profile.commitment_config());
+    let transaction = Transaction::new_unsigned(Message::new(instructions, Some(payer)));
+    let config = RpcSimulateTransactionConfig {
+        sig_verify: false,
+        replace_recent_blockhash: true,
+        commitment: Some(profile.commitment_config()),
+        ..Default::default()
     };
+    let sim = client.simulate_transaction_with_config(&transaction, config)?;
+    Ok(Simulation {
+        err: sim.value.err.map(|e| e.to_string()),
+        logs: sim.value.logs.unwrap_or_default(),
+    })
+}
+
+/// Builds the instructions deploying `spec`, returning the OFT store PDA with them:
+/// the new mint and its metadata if any, the mint authority handover for a native OFT, then
+/// `init_oft`. The store PDA is derived from `token_escrow`, a fresh keypair that must co-sign;
+/// `init_oft` creates the escrow token account at that address. `mint` must also co-sign
+/// when it is created.
+pub fn build_init_oft_ixs(profile: &SolanaProfile, payer: &Pubkey, spec: &SolanaOftSpec, mint: &Pubkey, token_escrow: &Pubkey) -> Result<(Pubkey, Vec<Instruction>)> {
+    let client = OftClient::new(profile)?;
+    let oft_store = oft_client::oft_store_pda(&client.program_id, token_escrow);
+    let mut instructions = Vec::new();
 
-    // Fetch a recent blockhash for signed simulation transactions
-    let recent_blockhash = client.get_latest_blockhash()?;
-
-    for name in candidates.iter() {
-        let mut hasher = Sha256::new();
-        hasher.update(format!("global:{}", name));
-        let hash = hasher.finalize();
-        let disc = &hash[0..8];
-        println!("  {} -> {}", name, hex::encode(disc));
-
-        // Build a trial instruction using this discriminator and simulate it
-        let mut trial_data = Vec::new();
-        trial_data.extend_from_slice(disc);
-        args.serialize(&mut trial_data)?;
-
-        let trial_accounts = vec![AccountMeta::new(oft_config, false), AccountMeta::new(payer.pubkey(), true)];
-        let trial_inst = Instruction::new_with_bytes(program_id, &trial_data, trial_accounts);
-
-        let trial_tx = Transaction::new_signed_with_payer(
-            &[trial_inst],
-            Some(&payer.pubkey()),
-            &[&payer],
-            recent_blockhash,
-        );
-
-        match client.simulate_transaction(&trial_tx) {
-            Ok(sim) => {
-                if sim.value.err.is_none() {
-                    println!("    ✅ {} simulation returned no error (candidate likely correct). Logs:", name);
-                    if let Some(logs) = sim.value.logs {
-                        for l in logs { println!("      {}", l); }
-                    }
-                } else {
-                    println!("    ❌ {} simulation error: {:?}", name, sim.value.err);
+    let token_program = match &spec.mint {
+        MintSource::Create(mint_spec) => {
+            if mint_spec.decimals < DEFAULT_SHARED_DECIMALS {
+                anyhow::bail!("Mint decimals ({}) must be at least the shared decimals ({})", mint_spec.decimals, DEFAULT_SHARED_DECIMALS);
+            }
+            let token_program = if mint_spec.token_2022 { oft_client::TOKEN_2022_PROGRAM_ID } else { oft_client::TOKEN_PROGRAM_ID };
+            let lamports = client.rpc.get_minimum_balance_for_rent_exemption(spl::MINT_LEN)?;
+            instructions.push(ComputeBudgetInstruction::set_compute_unit_limit(CREATE_MINT_COMPUTE_UNITS));
+            instructions.extend(spl::create_mint_ixs(payer, mint, &token_program, mint_spec.decimals, payer, lamports));
+            if let Some(metadata) = &mint_spec.metadata {
+                instructions.push(spl::create_metadata_ix(mint, payer, payer, payer, &metadata.name, &metadata.symbol, &metadata.uri)?);
+            }
+            token_program
+        }
+        MintSource::Existing(_) => {
+            let (token_program, existing) = client.mint(mint)?;
+            if spec.oft_type == OFTType::Native {
+                // The store PDA is new, so only the deployer can hand authority over in the same transaction
+                match existing.mint_authority {
+                    None => anyhow::bail!("Mint {} has no mint authority, so it cannot back a native OFT. Use the adapter type.", mint),
+                    Some(authority) if authority != *payer => anyhow::bail!(
+                        "Mint authority of {} is {}, not the deployer {}. Transfer it to the deployer first or use the adapter type.",
+                        mint, authority, payer
+                    ),
+                    Some(_) => {}
+                }
+                if existing.supply > 0 {
+                    println!("ℹ️  {} tokens already exist on {}; they stay spendable and can be sent through the native OFT", existing.supply, mint);
                 }
-            },
-            Err(se) => {
-                println!("    ⚠️ Simulation RPC error for {}: {}", name, se);
             }
+            token_program
         }
-    }
+    };
 
-    let mut data = Vec::new();
-    data.extend_from_slice(&discriminator);
-    args.serialize(&mut data)?;
+    // The native store mints on receive, so it must hold mint authority before the first transfer
+    if spec.oft_type == OFTType::Native {
+        instructions.push(spl::set_mint_authority_ix(&token_program, mint, payer, Some(&oft_store)));
+    }
+    let params = InitOFTParams {
+        oft_type: spec.oft_type,
+        admin: *payer,
+        shared_decimals: DEFAULT_SHARED_DECIMALS,
+        endpoint_program: Some(client.endpoint),
+    };
+    instructions.push(client.init_oft_ix(payer, mint, token_escrow, &token_program, params)?);
+    Ok((oft_store, instructions))
+}
 
-    // Debug: print instruction payload and addresses
-    println!("🔧 Instruction data (hex): {}", hex::encode(&data));
-    println!("🔧 Peer address (hex): {}", hex::encode(peer_address));
-    println!("🔧 OFT config: {}", oft_config);
+/// Decimals of the Solana OFT: read from the store and its mint if `oft_store` exists,
+/// otherwise the mint's decimals (or the ones it will be created with) and the default shared decimals.
+pub fn oft_decimals(profile: &SolanaProfile, spec: &SolanaOftSpec, oft_store: Option<&Pubkey>) -> Result<Decimals> {
+    let client = OftClient::new(profile)?;
+    let deployed = match oft_store {
+        Some(store) => account_exists(profile, store)?.then_some(store),
+        None => None,
+    };
+    if let Some(oft_store) = deployed {
+        let store = client.oft_store(oft_store)?;
+        let local = client.mint(&store.token_mint)?.1.decimals;
+        let rate_decimals = preflight::rate_decimals(store.ld2sd_rate).with_context(|| format!("OFT store {}", oft_store))?;
+        let shared = local.saturating_sub(rate_decimals);
+        return Ok(Decimals { local, shared, deployed: true });
+    }
+    let local = match &spec.mint {
+        MintSource::Create(mint_spec) => mint_spec.decimals,
+        MintSource::Existing(mint) => client.mint(&Pubkey::from_str(mint)?)?.1.decimals,
+    };
+    Ok(Decimals { local, shared: DEFAULT_SHARED_DECIMALS, deployed: false })
+}
 
-    let accounts = vec![
-        AccountMeta::new(oft_config, false), 
-        AccountMeta::new(payer.pubkey(), true), 
-    ];
+/// Builds `set_peer_config` registering `peer_address` for `remote_eid`.
+pub fn build_set_peer_ix(profile: &SolanaProfile, oft_store: &Pubkey, admin: &Pubkey, remote_eid: u32, peer_address: [u8; 32]) -> Result<Instruction> {
+    OftClient::new(profile)?.set_peer_config_ix(admin, oft_store, SetPeerConfigParams {
+        remote_eid,
+        config: PeerConfigParam::PeerAddress(peer_address),
+    })
+}
 
-    let instruction = Instruction::new_with_bytes(program_id, &data, accounts);
-
-    let recent_blockhash = client.get_latest_blockhash()?;
-    let transaction = Transaction::new_signed_with_payer(
-        &[instruction],
-        Some(&payer.pubkey()),
-        &[&payer],
-        recent_blockhash,
-    );
-
-    match client.send_and_confirm_transaction(&transaction) {
-        Ok(sig) => {
-            println!("✅ Solana Peer Set! Tx: {}", sig);
-            Ok(())
-        },
-        Err(e) => {
-            println!("❌ Transaction failed: {}", e);
-            println!("🔍 Simulating transaction to fetch program logs...");
-            match client.simulate_transaction(&transaction) {
-                Ok(sim) => {
-                    if let Some(logs) = sim.value.logs {
-                        println!("📜 Program logs:");
-                        for l in logs { println!("{}", l); }
-                    } else { println!("📜 No logs returned in simulation."); }
-                    if let Some(err) = sim.value.err {
-                        println!("Simulated instruction error: {:?}", err);
-                    }
-                },
-                Err(se) => {
-                    println!("Simulation RPC error: {}", se);
-                }
+/// Deploys the Solana OFT described by `spec` in a single transaction, returning the mint,
+/// the OFT store and the signature.
+pub async fn init_oft(profile: &SolanaProfile, payer: &KeySource, spec: &SolanaOftSpec) -> Result<(Pubkey, Pubkey, Signature)> {
+    let payer = payer.solana_signer()?;
+    let token_escrow = Keypair::new();
+    let new_mint = Keypair::new();
+    let mint = match &spec.mint {
+        MintSource::Existing(mint) => Pubkey::from_str(mint)?,
+        MintSource::Create(mint_spec) => {
+            println!(
+                "🪙 Creating {} mint {} ({} decimals)",
+                if mint_spec.token_2022 { "Token-2022" } else { "SPL Token" }, new_mint.pubkey(), mint_spec.decimals
+            );
+            if let Some(metadata) = &mint_spec.metadata {
+                println!("🏷️  Metadata: {} ({}) {}", metadata.name, metadata.symbol, metadata.uri);
             }
-            return Err(anyhow::anyhow!(format!("Failed to send tx: {}", e)));
+            new_mint.pubkey()
         }
+    };
+    println!("🛠️  Initializing Solana OFT ({}) for Mint: {}", oft_type_name(spec.oft_type), mint);
+
+    let (oft_store, instructions) = build_init_oft_ixs(profile, &payer.pubkey(), spec, &mint, &token_escrow.pubkey())?;
+    println!("📍 OFT store: {} (escrow {})", oft_store, token_escrow.pubkey());
+    if spec.oft_type == OFTType::Native {
+        println!("🔑 Mint authority moves to the OFT store");
+    }
+
+    println!("🚀 Sending Init Transaction...");
+    let mut signers = vec![&token_escrow];
+    if let MintSource::Create(_) = spec.mint {
+        signers.push(&new_mint);
+    }
+    let sig = OftClient::new(profile)?
+        .send_transaction(&instructions, payer.as_ref(), &signers)
+        .context("Failed to send init_oft tx")?;
+    println!("✅ Solana OFT Initialized! Tx: {}", sig);
+    Ok((mint, oft_store, sig))
+}
+
+/// Signed by the store admin; returns the signature unless the instruction was exported.
+pub async fn set_peer_solana(profile: &SolanaProfile, submit: &mut Submit<'_>, chain: &str, oft_store: Pubkey, remote_eid: u32, peer_address: [u8; 32]) -> Result<Option<Signature>> {
+    println!("🔗 Wiring Solana -> EID {}...", remote_eid);
+
+    let client = OftClient::new(profile)?;
+    let admin = submit.solana_authority(|| Ok(client.oft_store(&oft_store)?.admin))?;
+    let instruction = build_set_peer_ix(profile, &oft_store, &admin, remote_eid, peer_address)?;
+    let check = Check::peer(&oft_store, remote_eid, PeerField::PeerAddress, format!("0x{}", hex::encode(peer_address)));
+    let sig = submit.solana(&client, chain, &admin, &[instruction], "set_peer_config", vec![check])?;
+    if let Some(sig) = &sig {
+        println!("✅ Solana Peer Set! Tx: {}", sig);
+    }
+    Ok(sig)
+}
+
+/// Sets the enforced options for `remote_eid` unless the peer config already has them,
+/// returning the signature if a transaction was sent.
+pub async fn set_enforced_options_solana(profile: &SolanaProfile, submit: &mut Submit<'_>, chain: &str, oft_store: Pubkey, remote_eid: u32, spec: &EnforcedOptionsSpec) -> Result<Option<Signature>> {
+    let (send, send_and_call) = (spec.send(), spec.send_and_call());
+    options::assert_type_3(&send)?;
+    options::assert_type_3(&send_and_call)?;
+    let client = OftClient::new(profile)?;
+    // `set_peer_config` creates a missing peer config, which starts without enforced options
+    let (current_send, current_send_and_call) = client
+        .peer_config(&oft_store, remote_eid)?
+        .map_or((Vec::new(), Vec::new()), |peer| (peer.enforced_options.send, peer.enforced_options.send_and_call));
+    if current_send == send && current_send_and_call == send_and_call {
+        println!("⏭️  Enforced options for EID {} already set", remote_eid);
+        return Ok(None);
     }
-}
\ No newline at end of file
+
+    println!("⚙️  Enforcing options on Solana -> EID {} (send 0x{}, send+call 0x{})...", remote_eid, hex::encode(&send), hex::encode(&send_and_call));
+    let admin = submit.solana_authority(|| Ok(client.oft_store(&oft_store)?.admin))?;
+    let check = Check::peer(&oft_store, remote_eid, PeerField::EnforcedOptions, export::fmt_enforced_options(&send, &send_and_call));
+    let instruction = client.set_peer_config_ix(&admin, &oft_store, SetPeerConfigParams {
+        remote_eid,
+        config: PeerConfigParam::EnforcedOptions { send, send_and_call },
+    })?;
+    let sig = submit.solana(&client, chain, &admin, &[instruction], "set_peer_config", vec![check])?;
+    if let Some(sig) = &sig {
+        println!("✅ Solana Enforced Options Set! Tx: {}", sig);
+    }
+    Ok(sig)
+}
+
+/// Brings both limiters of the peer for `remote_eid` to `limits`, in one transaction with an
+/// instruction per changed direction. Returns the signature if anything was sent. A missing
+/// peer config counts as unlimited, since `set_peer_config` creates it without limiters.
+pub async fn set_rate_limits_solana(profile: &SolanaProfile, submit: &mut Submit<'_>, chain: &str, oft_store: Pubkey, remote_eid: u32, limits: &PeerRateLimits) -> Result<Option<Signature>> {
+    let client = OftClient::new(profile)?;
+    let (outbound, inbound) = client
+        .peer_config(&oft_store, remote_eid)?
+        .map_or((None, None), |peer| (peer.outbound_rate_limiter, peer.inbound_rate_limiter));
+    let mut configs = Vec::new();
+    if let Some(params) = rate_limit::reconcile(&outbound, &limits.outbound) {
+        configs.push(PeerConfigParam::OutboundRateLimit(params));
+    }
+    if let Some(params) = rate_limit::reconcile(&inbound, &limits.inbound) {
+        configs.push(PeerConfigParam::InboundRateLimit(params));
+    }
+    if configs.is_empty() {
+        println!("⏭️  Rate limits for EID {} already set", remote_eid);
+        return Ok(None);
+    }
+
+    println!("🚦 Reconciling Solana -> EID {} rate limits ({} change(s))...", remote_eid, configs.len());
+    let admin = submit.solana_authority(|| Ok(client.oft_store(&oft_store)?.admin))?;
+    let instructions = configs
+        .into_iter()
+        .map(|config| client.set_peer_config_ix(&admin, &oft_store, SetPeerConfigParams { remote_eid, config }))
+        .collect::<Result<Vec<_>>>()?;
+    let spec = |limit: &Option<RateLimitSpec>| export::fmt_rate_limit(limit.as_ref().map(|l| (l.capacity, l.refill_per_second)));
+    let checks = vec![
+        Check::peer(&oft_store, remote_eid, PeerField::OutboundRateLimit, spec(&limits.outbound)),
+        Check::peer(&oft_store, remote_eid, PeerField::InboundRateLimit, spec(&limits.inbound)),
+    ];
+    let sig = submit.solana(&client, chain, &admin, &instructions, "set_peer_config", checks)?;
+    if let Some(sig) = &sig {
+        println!("✅ Solana Rate Limits Set! Tx: {}", sig);
+    }
+    Ok(sig)
+}
@@ -0,0 +1,143 @@
+use borsh::BorshSerialize;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey,
+    pubkey::Pubkey,
+    system_instruction,
+    system_program,
+};
+use anyhow::Result;
+
+/// Size of a mint account without Token-2022 extensions.
+pub const MINT_LEN: usize = 82;
+
+/// Size of a token account without Token-2022 extensions.
+pub const TOKEN_ACCOUNT_LEN: usize = 165;
+
+pub const METADATA_PROGRAM_ID: Pubkey = pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey = pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+
+// Metaplex `DataV2` length limits.
+const MAX_NAME_LEN: usize = 32;
+const MAX_SYMBOL_LEN: usize = 10;
+const MAX_URI_LEN: usize = 200;
+
+// Token and Token-2022 share these instruction tags.
+const INITIALIZE_MINT2: u8 = 20;
+const SET_AUTHORITY: u8 = 6;
+const AUTHORITY_MINT_TOKENS: u8 = 0;
+
+const CREATE_METADATA_ACCOUNT_V3: u8 = 33;
+
+/// Base mint layout shared by Token and Token-2022; extensions follow it.
+pub struct Mint {
+    pub mint_authority: Option<Pubkey>,
+    pub supply: u64,
+    pub decimals: u8,
+}
+
+/// `COption<Pubkey>` as stored in token accounts: u32 tag, then the key.
+fn unpack_optional_pubkey(data: &[u8]) -> Option<Pubkey> {
+    (data[0..4] == [1, 0, 0, 0]).then(|| Pubkey::try_from(&data[4..36]).expect("32 bytes"))
+}
+
+pub fn unpack_mint(address: &Pubkey, data: &[u8]) -> Result<Mint> {
+    // is_initialized sits after mint_authority (36), supply (8) and decimals (1)
+    if data.len() < MINT_LEN || data[45] != 1 {
+        anyhow::bail!("{} is not an initialized mint", address);
+    }
+    Ok(Mint {
+        mint_authority: unpack_optional_pubkey(&data[0..36]),
+        supply: u64::from_le_bytes(data[36..44].try_into().expect("8 bytes")),
+        decimals: data[44],
+    })
+}
+
+/// Balance of a token account: mint (32) and owner (32) precede the amount.
+pub fn unpack_token_amount(address: &Pubkey, data: &[u8]) -> Result<u64> {
+    if data.len() < TOKEN_ACCOUNT_LEN {
+        anyhow::bail!("{} is not a token account", address);
+    }
+    Ok(u64::from_le_bytes(data[64..72].try_into().expect("8 bytes")))
+}
+
+/// Metaplex `DataV2` without creators, collection or uses.
+#[derive(BorshSerialize)]
+struct MetadataData {
+    name: String,
+    symbol: String,
+    uri: String,
+    seller_fee_basis_points: u16,
+}
+
+/// `COption<Pubkey>` as packed by the token programs' instruction encoding.
+fn pack_optional_pubkey(data: &mut Vec<u8>, key: Option<&Pubkey>) {
+    match key {
+        Some(key) => {
+            data.push(1);
+            data.extend_from_slice(key.as_ref());
+        }
+        None => data.push(0),
+    }
+}
+
+/// Allocates `mint` (a fresh keypair, which must sign) and initializes it with
+/// `mint_authority` and no freeze authority.
+pub fn create_mint_ixs(payer: &Pubkey, mint: &Pubkey, token_program: &Pubkey, decimals: u8, mint_authority: &Pubkey, lamports: u64) -> Vec<Instruction> {
+    let mut data = vec![INITIALIZE_MINT2, decimals];
+    data.extend_from_slice(mint_authority.as_ref());
+    pack_optional_pubkey(&mut data, None);
+    vec![
+        system_instruction::create_account(payer, mint, lamports, MINT_LEN as u64, token_program),
+        Instruction::new_with_bytes(*token_program, &data, vec![AccountMeta::new(*mint, false)]),
+    ]
+}
+
+/// `SetAuthority(MintTokens)`, moving mint authority from `current` to `new` (`None` revokes it).
+pub fn set_mint_authority_ix(token_program: &Pubkey, mint: &Pubkey, current: &Pubkey, new: Option<&Pubkey>) -> Instruction {
+    let mut data = vec![SET_AUTHORITY, AUTHORITY_MINT_TOKENS];
+    pack_optional_pubkey(&mut data, new);
+    Instruction::new_with_bytes(*token_program, &data, vec![
+        AccountMeta::new(*mint, false),
+        AccountMeta::new_readonly(*current, true),
+    ])
+}
+
+/// Associated token account of `owner` for `mint` under `token_program`.
+pub fn associated_token_address(owner: &Pubkey, token_program: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[owner.as_ref(), token_program.as_ref(), mint.as_ref()], &ASSOCIATED_TOKEN_PROGRAM_ID).0
+}
+
+pub fn metadata_pda(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"metadata", METADATA_PROGRAM_ID.as_ref(), mint.as_ref()], &METADATA_PROGRAM_ID).0
+}
+
+/// Metaplex `CreateMetadataAccountV3` for `mint`, signed by its current mint authority.
+/// `update_authority` must also sign; the metadata stays mutable.
+pub fn create_metadata_ix(mint: &Pubkey, mint_authority: &Pubkey, payer: &Pubkey, update_authority: &Pubkey, name: &str, symbol: &str, uri: &str) -> Result<Instruction> {
+    if name.len() > MAX_NAME_LEN || symbol.len() > MAX_SYMBOL_LEN || uri.len() > MAX_URI_LEN {
+        anyhow::bail!(
+            "Token metadata too long: name, symbol and URI are limited to {}, {} and {} bytes",
+            MAX_NAME_LEN, MAX_SYMBOL_LEN, MAX_URI_LEN
+        );
+    }
+    let mut data = vec![CREATE_METADATA_ACCOUNT_V3];
+    MetadataData {
+        name: name.to_string(),
+        symbol: symbol.to_string(),
+        uri: uri.to_string(),
+        seller_fee_basis_points: 0,
+    }
+    .serialize(&mut data)?;
+    // creators, collection, uses: None; is_mutable: true; collection_details: None
+    data.extend_from_slice(&[0, 0, 0, 1, 0]);
+    Ok(Instruction::new_with_bytes(METADATA_PROGRAM_ID, &data, vec![
+        AccountMeta::new(metadata_pda(mint), false),
+        AccountMeta::new_readonly(*mint, false),
+        AccountMeta::new_readonly(*mint_authority, true),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(*update_authority, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ]))
+}
@@ -0,0 +1,192 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use anyhow::{Result, Context};
+
+/// Finished deployment steps, persisted so a failed run can resume where it stopped.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct DeploymentState {
+    /// Keyed by chain name.
+    #[serde(default)]
+    pub chains: BTreeMap<String, ChainDeployment>,
+    #[serde(default)]
+    pub peers: Vec<PeerRecord>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum ChainDeployment {
+    Solana {
+        mint: String,
+        oft_pda: String,
+        signature: String,
+        /// What was requested, e.g. `adapter over mint ...`; absent in older state files.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        spec: Option<String>,
+    },
+    Evm {
+        chain_id: u32,
+        address: String,
+        tx_hash: Option<String>,
+        /// What was requested, e.g. `MyOFT name="..." symbol="..."`; absent in older state files.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        spec: Option<String>,
+    },
+}
+
+impl ChainDeployment {
+    pub fn spec(&self) -> Option<&str> {
+        match self {
+            ChainDeployment::Solana { spec, .. } | ChainDeployment::Evm { spec, .. } => spec.as_deref(),
+        }
+    }
+}
+
+/// `from` has `peer` (bytes32 hex) registered for `remote_eid`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PeerRecord {
+    pub from: String,
+    pub to: String,
+    pub remote_eid: u32,
+    pub peer: String,
+    pub tx: Option<String>,
+}
+
+impl DeploymentState {
+    pub fn path_for(name: &str) -> PathBuf {
+        Path::new("deployments").join(format!("{}.json", name))
+    }
+
+    /// Loads the state at `path`, or starts empty if it does not exist or `fresh` is set.
+    pub fn load(path: &Path, fresh: bool) -> Result<Self> {
+        if fresh || !path.exists() {
+            return Ok(Self::default());
+        }
+        let raw = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read state file {}", path.display()))?;
+        let state = serde_json::from_str(&raw)
+            .with_context(|| format!("Corrupt state file {}", path.display()))?;
+        println!("📂 Resuming from {}", path.display());
+        Ok(state)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write state file {}", path.display()))
+    }
+
+    pub fn chain(&self, name: &str) -> Option<&ChainDeployment> {
+        self.chains.get(name)
+    }
+
+    /// Returns the recorded Solana mint and OFT PDA.
+    pub fn solana_oft(&self, name: &str) -> Option<(String, String)> {
+        match self.chain(name) {
+            Some(ChainDeployment::Solana { mint, oft_pda, .. }) => Some((mint.clone(), oft_pda.clone())),
+            _ => None,
+        }
+    }
+
+    /// Returns the recorded Solana OFT PDA, refusing to reuse it for a different mint.
+    pub fn solana_pda(&self, name: &str, mint: &str) -> Result<Option<String>> {
+        match self.solana_oft(name) {
+            Some((recorded, oft_pda)) => {
+                if recorded != mint {
+                    anyhow::bail!(
+                        "State for '{}' was recorded for mint {}, not {}. Rerun with --fresh to start over.",
+                        name, recorded, mint
+                    );
+                }
+                Ok(Some(oft_pda))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Refuses to reuse what was deployed on `name` for a different `spec`.
+    pub fn check_spec(&self, name: &str, spec: &str) -> Result<()> {
+        if let Some(recorded) = self.chain(name).and_then(ChainDeployment::spec) {
+            if recorded != spec {
+                anyhow::bail!(
+                    "State for '{}' was recorded for {}, not {}. Rerun with --fresh to start over.",
+                    name, recorded, spec
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the recorded EVM OFT address, refusing to reuse it for a different `spec` or
+    /// when it is not a valid address.
+    pub fn evm_oft(&self, name: &str, spec: &str) -> Result<Option<String>> {
+        self.check_spec(name, spec)?;
+        let address = self.evm_address(name);
+        if let Some(address) = &address {
+            crate::utils::pad_evm_address(address).with_context(|| format!("Malformed state entry '{}'", name))?;
+        }
+        Ok(address)
+    }
+
+    pub fn evm_address(&self, name: &str) -> Option<String> {
+        match self.chain(name) {
+            Some(ChainDeployment::Evm { address, .. }) => Some(address.clone()),
+            _ => None,
+        }
+    }
+
+    /// True if `from` already has exactly this peer set for `remote_eid`.
+    pub fn has_peer(&self, from: &str, remote_eid: u32, peer: &str) -> bool {
+        self.peers.iter().any(|p| p.from == from && p.remote_eid == remote_eid && p.peer == peer)
+    }
+
+    pub fn record_peer(&mut self, record: PeerRecord) {
+        self.peers.retain(|p| !(p.from == record.from && p.remote_eid == record.remote_eid));
+        self.peers.push(record);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(spec: Option<&str>) -> DeploymentState {
+        let mut state = DeploymentState::default();
+        state.chains.insert("sepolia".to_string(), ChainDeployment::Evm {
+            chain_id: 11155111,
+            address: "0x0000000000000000000000000000000000000001".to_string(),
+            tx_hash: None,
+            spec: spec.map(str::to_string),
+        });
+        state
+    }
+
+    #[test]
+    fn reuses_deployment_with_the_same_spec() {
+        let recorded = state(Some("MyOFT name \"A\" symbol \"A\"")).evm_oft("sepolia", "MyOFT name \"A\" symbol \"A\"").unwrap();
+        assert_eq!(recorded.as_deref(), Some("0x0000000000000000000000000000000000000001"));
+    }
+
+    #[test]
+    fn refuses_deployment_with_another_spec() {
+        let err = state(Some("MyOFT name \"A\" symbol \"A\"")).evm_oft("sepolia", "MyOFT name \"B\" symbol \"B\"").unwrap_err();
+        assert!(err.to_string().contains("--fresh"), "{}", err);
+    }
+
+    #[test]
+    fn names_malformed_entries() {
+        let mut state = state(None);
+        if let Some(ChainDeployment::Evm { address, .. }) = state.chains.get_mut("sepolia") {
+            *address = "0x1234".to_string();
+        }
+        let err = state.evm_oft("sepolia", "MyOFT").unwrap_err();
+        assert!(err.to_string().contains("'sepolia'"), "{}", err);
+    }
+
+    #[test]
+    fn reuses_deployment_recorded_without_spec() {
+        assert!(state(None).evm_oft("sepolia", "MyOFT name \"B\" symbol \"B\"").unwrap().is_some());
+    }
+}
@@ -0,0 +1,348 @@
+use serde::Serialize;
+use std::path::Path;
+use std::str::FromStr;
+use solana_sdk::pubkey::Pubkey;
+use anyhow::{Result, Context};
+use crate::deploy::{Deployed, SOLANA_CHAIN};
+use crate::network::{EvmProfile, NetworkProfile};
+use crate::oft_client::OftClient;
+use crate::state::{ChainDeployment, DeploymentState};
+use crate::evm_ops;
+
+/// One chain of the audited deployment.
+pub struct AuditChain {
+    pub name: String,
+    pub eid: u32,
+    pub oft: Deployed,
+    /// Set for EVM chains.
+    pub evm: Option<EvmProfile>,
+}
+
+impl AuditChain {
+    /// The deployment recorded in `state`, with EIDs and RPCs from `profile`.
+    pub fn from_state(state: &DeploymentState, profile: &NetworkProfile) -> Result<Vec<Self>> {
+        let mut chains = Vec::new();
+        for (name, chain) in &state.chains {
+            let malformed = || format!("Malformed state entry '{}'", name);
+            chains.push(match chain {
+                ChainDeployment::Solana { oft_pda, .. } => AuditChain {
+                    name: name.clone(),
+                    eid: profile.solana.eid,
+                    oft: Deployed::Solana(Pubkey::from_str(oft_pda).with_context(malformed)?),
+                    evm: None,
+                },
+                ChainDeployment::Evm { chain_id, address, .. } => Self::evm(profile.evm(*chain_id)?, address).with_context(malformed)?,
+            });
+        }
+        Ok(chains)
+    }
+
+    /// Parses `--evm CHAIN_ID=ADDRESS`.
+    pub fn parse_evm(value: &str, profile: &NetworkProfile) -> Result<Self> {
+        let (chain_id, address) = value
+            .split_once('=')
+            .ok_or_else(|| anyhow::anyhow!("Invalid --evm '{}', expected CHAIN_ID=ADDRESS", value))?;
+        let chain_id: u32 = chain_id.parse().with_context(|| format!("Invalid chain ID in --evm '{}'", value))?;
+        Self::evm(profile.evm(chain_id)?, address)
+    }
+
+    pub fn solana(oft_store: Pubkey, profile: &NetworkProfile) -> Self {
+        AuditChain { name: SOLANA_CHAIN.to_string(), eid: profile.solana.eid, oft: Deployed::Solana(oft_store), evm: None }
+    }
+
+    fn evm(profile: &EvmProfile, address: &str) -> Result<Self> {
+        crate::utils::pad_evm_address(address)?;
+        Ok(AuditChain {
+            name: profile.name.clone(),
+            eid: profile.eid,
+            oft: Deployed::Evm(address.to_string()),
+            evm: Some(profile.clone()),
+        })
+    }
+}
+
+/// Live wiring of every directed pair of chains.
+#[derive(Serialize)]
+pub struct StatusReport {
+    pub network: String,
+    pub chains: Vec<ChainStatus>,
+    pub links: Vec<LinkStatus>,
+}
+
+#[derive(Serialize)]
+pub struct ChainStatus {
+    pub name: String,
+    pub eid: u32,
+    pub oft: String,
+    pub deployed: bool,
+}
+
+#[derive(Serialize)]
+pub struct LinkStatus {
+    pub from: String,
+    pub to: String,
+    pub remote_eid: u32,
+    pub status: Wiring,
+    pub expected: String,
+    /// Peer read from `from`; `None` if unset or `from` is not deployed.
+    pub actual: Option<String>,
+    /// The state file has this peer recorded as set.
+    pub recorded: bool,
+    /// `to -> from` is wired too.
+    pub reverse_wired: bool,
+    pub note: Option<String>,
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Wiring {
+    Wired,
+    Missing,
+    /// A peer is set but is not the remote OFT.
+    Mismatched,
+    /// The OFT on `from` does not exist.
+    NotDeployed,
+}
+
+impl Wiring {
+    fn name(self) -> &'static str {
+        match self {
+            Wiring::Wired => "wired",
+            Wiring::Missing => "missing",
+            Wiring::Mismatched => "mismatched",
+            Wiring::NotDeployed => "not-deployed",
+        }
+    }
+}
+
+fn hex32(bytes: [u8; 32]) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
+
+/// Explains a mismatched peer when it matches a known mistake.
+fn diagnose(actual: [u8; 32], to: &AuditChain, chains: &[AuditChain], oft_bytes: &[[u8; 32]], solana_mints: &[(Pubkey, Pubkey)]) -> Option<String> {
+    if let Some((other, _)) = chains.iter().zip(oft_bytes).find(|(_, bytes)| **bytes == actual) {
+        return Some(format!("points at the {} OFT instead of {}", other.name, to.name));
+    }
+    match &to.oft {
+        Deployed::Evm(addr) => {
+            let raw = crate::utils::parse_hex(addr).ok()?;
+            (actual[..20] == raw[..]).then(|| "EVM address is right-padded; peers must be left-padded to 32 bytes".to_string())
+        }
+        Deployed::Solana(store) => solana_mints
+            .iter()
+            .find(|(s, mint)| s == store && mint.to_bytes() == actual)
+            .map(|_| "points at the Solana mint; the peer must be the OFT store PDA".to_string()),
+    }
+}
+
+/// Reads the OFT of every chain and the peer each one has for every other chain.
+pub async fn audit(network: &str, solana: Option<&OftClient>, chains: &[AuditChain], state: Option<&DeploymentState>) -> Result<StatusReport> {
+    let mut peers = Vec::new();
+    let mut solana_mints = Vec::new();
+    let mut chain_status = Vec::new();
+
+    for chain in chains {
+        let remotes: Vec<&AuditChain> = chains.iter().filter(|c| c.name != chain.name).collect();
+        let read = match (&chain.oft, &chain.evm) {
+            (Deployed::Solana(store), _) => {
+                let client = solana.context("Solana chain audited without a Solana client")?;
+                match client.account_data(store)? {
+                    None => None,
+                    Some(_) => {
+                        solana_mints.push((*store, client.oft_store(store)?.token_mint));
+                        let mut values = Vec::new();
+                        for remote in &remotes {
+                            values.push(client.peer_config(store, remote.eid)?.map(|p| p.peer_address));
+                        }
+                        Some(values)
+                    }
+                }
+            }
+            (Deployed::Evm(addr), Some(profile)) => {
+                let mut values = Vec::new();
+                let mut deployed = true;
+                for remote in &remotes {
+                    match evm_ops::read_peer(profile, addr, remote.eid).await? {
+                        Some(peer) => {
+                            let bytes = crate::utils::parse_bytes32(&peer)?;
+                            values.push((bytes != [0u8; 32]).then_some(bytes));
+                        }
+                        None => {
+                            deployed = false;
+                            break;
+                        }
+                    }
+                }
+                deployed.then_some(values)
+            }
+            (Deployed::Evm(_), None) => anyhow::bail!("EVM chain {} has no profile", chain.name),
+        };
+        chain_status.push(ChainStatus {
+            name: chain.name.clone(),
+            eid: chain.eid,
+            oft: match &chain.oft {
+                Deployed::Evm(addr) => addr.clone(),
+                Deployed::Solana(store) => store.to_string(),
+            },
+            deployed: read.is_some(),
+        });
+        peers.push(read);
+    }
+
+    let links = link_statuses(chains, &peers, &solana_mints, state)?;
+    Ok(StatusReport { network: network.to_string(), chains: chain_status, links })
+}
+
+/// Peers read per chain: for each, its peer of every other chain in order, or `None` if its
+/// OFT is missing.
+type PeerReads = [Option<Vec<Option<[u8; 32]>>>];
+
+/// Peer `from` has for `to`; `None` if `from` is not deployed. Reads skip `from` itself, so
+/// chains after it sit one slot earlier.
+fn peer_of(peers: &PeerReads, from: usize, to: usize) -> Option<Option<[u8; 32]>> {
+    let slot = if to > from { to - 1 } else { to };
+    peers[from].as_ref().map(|values| values[slot])
+}
+
+/// Wiring of every directed pair of `chains`, from the peers `audit` read.
+fn link_statuses(chains: &[AuditChain], peers: &PeerReads, solana_mints: &[(Pubkey, Pubkey)], state: Option<&DeploymentState>) -> Result<Vec<LinkStatus>> {
+    let oft_bytes = chains.iter().map(|c| c.oft.to_bytes32()).collect::<Result<Vec<_>>>()?;
+    let mut links = Vec::new();
+    for (i, from) in chains.iter().enumerate() {
+        for (j, to) in chains.iter().enumerate().filter(|(j, _)| *j != i) {
+            let expected = oft_bytes[j];
+            let actual = peer_of(peers, i, j);
+            let (status, note) = match actual {
+                None => (Wiring::NotDeployed, None),
+                Some(None) => (Wiring::Missing, None),
+                Some(Some(peer)) if peer == expected => (Wiring::Wired, None),
+                Some(Some(peer)) => (Wiring::Mismatched, diagnose(peer, to, chains, &oft_bytes, solana_mints)),
+            };
+            let expected_hex = hex32(expected);
+            links.push(LinkStatus {
+                from: from.name.clone(),
+                to: to.name.clone(),
+                remote_eid: to.eid,
+                status,
+                expected: expected_hex.clone(),
+                actual: actual.flatten().map(hex32),
+                recorded: state.is_some_and(|s| s.has_peer(&from.name, to.eid, &expected_hex)),
+                reverse_wired: peer_of(peers, j, i).flatten() == Some(oft_bytes[i]),
+                note,
+            });
+        }
+    }
+    Ok(links)
+}
+
+/// Prints the report as tables and writes its JSON to `json_path`.
+pub fn report(report: &StatusReport, json_path: &Path) -> Result<()> {
+    println!("\n🩺 Wiring status ({})", report.network);
+    println!("\n{:<20} {:>6}  {:<10} OFT", "CHAIN", "EID", "DEPLOYED");
+    for chain in &report.chains {
+        println!("{:<20} {:>6}  {:<10} {}", chain.name, chain.eid, if chain.deployed { "yes" } else { "no" }, chain.oft);
+    }
+
+    println!("\n   {:<20} {:<20} {:>6}  {:<12} {:<8} NOTES", "FROM", "TO", "EID", "STATUS", "REVERSE");
+    for link in &report.links {
+        let marker = match link.status {
+            Wiring::Wired => "✅",
+            Wiring::Missing | Wiring::NotDeployed => "⬜",
+            Wiring::Mismatched => "❌",
+        };
+        let mut notes = Vec::new();
+        if link.status == Wiring::Mismatched {
+            notes.push(format!("peer {} (expected {})", link.actual.as_deref().unwrap_or("-"), link.expected));
+        }
+        if let Some(note) = &link.note {
+            notes.push(note.clone());
+        }
+        if link.recorded && link.status != Wiring::Wired {
+            notes.push("state file records this peer as set".to_string());
+        }
+        println!(
+            "{} {:<20} {:<20} {:>6}  {:<12} {:<8} {}",
+            marker,
+            link.from,
+            link.to,
+            link.remote_eid,
+            link.status.name(),
+            if link.reverse_wired { "wired" } else { "-" },
+            notes.join("; ")
+        );
+    }
+
+    let wired = report.links.iter().filter(|l| l.status == Wiring::Wired).count();
+    let one_way = report.links.iter().filter(|l| l.status == Wiring::Wired && !l.reverse_wired).count();
+    println!("\n{} of {} direction(s) wired, {} one-way", wired, report.links.len(), one_way);
+
+    if let Some(dir) = json_path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(json_path, serde_json::to_string_pretty(report)?)?;
+    println!("📄 Machine-readable status written to {}", json_path.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::PeerRecord;
+
+    fn chain(name: &str, eid: u32, oft: Deployed) -> AuditChain {
+        AuditChain { name: name.to_string(), eid, oft, evm: None }
+    }
+
+    #[test]
+    fn peer_of_skips_the_reading_chain() {
+        let peers = vec![Some(vec![Some([1; 32]), Some([2; 32])]), None, Some(vec![Some([3; 32]), None])];
+        assert_eq!(peer_of(&peers, 0, 1), Some(Some([1; 32])));
+        assert_eq!(peer_of(&peers, 0, 2), Some(Some([2; 32])));
+        assert_eq!(peer_of(&peers, 1, 0), None);
+        assert_eq!(peer_of(&peers, 2, 0), Some(Some([3; 32])));
+        assert_eq!(peer_of(&peers, 2, 1), Some(None));
+    }
+
+    #[test]
+    fn diagnoses_a_three_chain_mesh() {
+        let store = Pubkey::new_unique();
+        let arbitrum = "0x00000000000000000000000000000000000000aa";
+        let base = "0x00000000000000000000000000000000000000bb";
+        let chains = vec![
+            chain("solana", 30168, Deployed::Solana(store)),
+            chain("arbitrum", 30110, Deployed::Evm(arbitrum.to_string())),
+            chain("base", 30184, Deployed::Evm(base.to_string())),
+        ];
+        let oft: Vec<[u8; 32]> = chains.iter().map(|c| c.oft.to_bytes32().unwrap()).collect();
+        let mut right_padded = [0u8; 32];
+        right_padded[..20].copy_from_slice(&crate::utils::parse_hex(base).unwrap());
+        let peers = vec![
+            // solana: arbitrum wired, base missing
+            Some(vec![Some(oft[1]), None]),
+            // arbitrum: solana wired, base right-padded
+            Some(vec![Some(oft[0]), Some(right_padded)]),
+            // base: solana wired, arbitrum set to the Solana store
+            Some(vec![Some(oft[0]), Some(oft[0])]),
+        ];
+        let mut state = DeploymentState::default();
+        state.record_peer(PeerRecord { from: "arbitrum".to_string(), to: "base".to_string(), remote_eid: 30184, peer: hex32(oft[2]), tx: None });
+
+        let links = link_statuses(&chains, &peers, &[(store, Pubkey::new_unique())], Some(&state)).unwrap();
+        let summary: Vec<(&str, &str, &str, Option<&str>, bool)> = links
+            .iter()
+            .map(|l| (l.from.as_str(), l.to.as_str(), l.status.name(), l.note.as_deref(), l.reverse_wired))
+            .collect();
+        assert_eq!(summary, vec![
+            ("solana", "arbitrum", "wired", None, true),
+            ("solana", "base", "missing", None, true),
+            ("arbitrum", "solana", "wired", None, true),
+            ("arbitrum", "base", "mismatched", Some("EVM address is right-padded; peers must be left-padded to 32 bytes"), false),
+            ("base", "solana", "wired", None, false),
+            ("base", "arbitrum", "mismatched", Some("points at the solana OFT instead of arbitrum"), false),
+        ]);
+        assert_eq!(links[3].actual.as_deref(), Some(hex32(right_padded).as_str()));
+        assert!(links[3].recorded);
+        assert!(!links[1].recorded);
+    }
+}
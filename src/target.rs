@@ -0,0 +1,95 @@
+use std::path::PathBuf;
+use anyhow::Result;
+use crate::export::{ExportArgs, Exporter, Submit};
+use crate::network::{self, NetworkProfile};
+use crate::state::DeploymentState;
+use crate::signer::SignerArgs;
+use crate::status::AuditChain;
+
+/// A recorded deployment, loaded from its state file, and the signers to act on it with.
+#[derive(clap::Args)]
+pub struct DeploymentArgs {
+    /// State file of the deployment; defaults to deployments/<network>.json
+    #[arg(long)]
+    pub state: Option<PathBuf>,
+    #[arg(long, value_enum, default_value = "testnet")]
+    pub network: network::Network,
+    /// Profile file used with `--network custom`
+    #[arg(long, default_value = "network.toml")]
+    pub network_file: PathBuf,
+    #[command(flatten)]
+    pub signer: SignerArgs,
+    #[command(flatten)]
+    pub export: ExportArgs,
+}
+
+/// A chain of a recorded deployment, picked by name from its state file.
+#[derive(clap::Args)]
+pub struct ChainArgs {
+    /// Chain as named in the state file (`solana` or an EVM profile name for `deploy`)
+    #[arg(long, default_value = crate::deploy::SOLANA_CHAIN)]
+    pub chain: String,
+    #[command(flatten)]
+    pub deployment: DeploymentArgs,
+}
+
+/// The selected chain together with every other chain of the deployment.
+pub struct Resolved {
+    pub profile: NetworkProfile,
+    pub chain: AuditChain,
+    pub others: Vec<AuditChain>,
+}
+
+impl DeploymentArgs {
+    /// The network profile and every chain recorded in the state file.
+    pub fn load(&self) -> Result<(NetworkProfile, Vec<AuditChain>)> {
+        let profile = NetworkProfile::load(self.network, &self.network_file)?;
+        let state_path = self.state_path();
+        if !state_path.exists() {
+            anyhow::bail!("No state file at {}; pass --state", state_path.display());
+        }
+        let state = DeploymentState::load(&state_path, false)?;
+        let chains = AuditChain::from_state(&state, &profile)?;
+        Ok((profile, chains))
+    }
+
+    pub fn state_path(&self) -> PathBuf {
+        self.state.clone().unwrap_or_else(|| DeploymentState::path_for(&self.network.name()))
+    }
+
+    /// Solana actions on `chain` signed with its configured signer, or exported when `exporter` is set.
+    pub fn solana_submit<'a>(&self, chain: &str, exporter: &'a mut Option<Exporter>) -> Result<Submit<'a>> {
+        Submit::new(exporter, || Ok(self.signer.load()?.solana(chain)))
+    }
+
+    /// EVM actions on `chain` signed with its configured signer, or exported when `exporter` is set.
+    pub fn evm_submit<'a>(&self, chain: &str, exporter: &'a mut Option<Exporter>) -> Result<Submit<'a>> {
+        Submit::new(exporter, || Ok(self.signer.load()?.evm(chain)))
+    }
+}
+
+impl ChainArgs {
+    pub fn resolve(&self) -> Result<Resolved> {
+        let (profile, chains) = self.deployment.load()?;
+        let (mut chain, others): (Vec<AuditChain>, Vec<AuditChain>) = chains.into_iter().partition(|c| c.name == self.chain);
+        let chain = chain.pop().ok_or_else(|| {
+            let known: Vec<&str> = others.iter().map(|c| c.name.as_str()).collect();
+            anyhow::anyhow!("Chain '{}' is not in {}. Known: {}", self.chain, self.deployment.state_path().display(), known.join(", "))
+        })?;
+        Ok(Resolved { profile, chain, others })
+    }
+}
+
+impl Resolved {
+    /// EID of `peer`, given as an EID or a chain name from the state file.
+    pub fn peer_eid(&self, peer: &str) -> Result<u32> {
+        if let Ok(eid) = peer.parse() {
+            return Ok(eid);
+        }
+        self.others
+            .iter()
+            .find(|c| c.name == peer)
+            .map(|c| c.eid)
+            .ok_or_else(|| anyhow::anyhow!("Peer '{}' is neither an EID nor a chain in the state file", peer))
+    }
+}
@@ -1,27 +1,50 @@
 use solana_sdk::pubkey::Pubkey;
-use hex;
 
-pub fn pad_evm_address(addr_str: &str) -> [u8; 32] {
-    let clean_hex = addr_str.trim_start_matches("0x");
-    let bytes = hex::decode(clean_hex).expect("Invalid Hex String");
+/// Left-pads a 20-byte hex EVM address to the 32 bytes OApp peers are stored as.
+pub fn pad_evm_address(addr_str: &str) -> anyhow::Result<[u8; 32]> {
+    let bytes = parse_hex(addr_str)?;
+    if bytes.len() != 20 {
+        anyhow::bail!("{} is not a 20-byte EVM address", addr_str);
+    }
     let mut padded = [0u8; 32];
     padded[12..].copy_from_slice(&bytes);
-    padded
+    Ok(padded)
 }
 
-pub fn pubkey_to_hex32(pubkey: &Pubkey) -> String {
-    let bytes = pubkey.to_bytes();
-    format!("0x{}", hex::encode(bytes))
+/// Parses a peer/recipient address: 20-byte EVM hex (left-padded), 32-byte hex, or a base58 pubkey.
+pub fn parse_bytes32(value: &str) -> anyhow::Result<[u8; 32]> {
+    if let Ok(bytes) = hex::decode(value.trim_start_matches("0x")) {
+        match bytes.len() {
+            20 => return pad_evm_address(value),
+            32 => return Ok(bytes.try_into().expect("length checked")),
+            _ => {}
+        }
+    }
+    match value.parse::<Pubkey>() {
+        Ok(pubkey) => Ok(pubkey.to_bytes()),
+        Err(_) => anyhow::bail!("{} is neither a 20/32-byte hex address nor a base58 pubkey", value),
+    }
 }
 
-pub fn parse_forge_output(output: &str) -> Option<String> {
-    for line in output.lines() {
-        if line.contains("DEPLOYED_ADDR:") {
-            let parts: Vec<&str> = line.split("DEPLOYED_ADDR:").collect();
-            if parts.len() > 1 {
-                return Some(parts[1].trim().to_string());
-            }
-        }
+pub fn parse_hex(value: &str) -> anyhow::Result<Vec<u8>> {
+    hex::decode(value.trim_start_matches("0x")).map_err(|e| anyhow::anyhow!("Invalid hex {}: {}", value, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pads_evm_addresses_on_the_left() {
+        let padded = pad_evm_address("0x000000000000000000000000000000000000dEaD").unwrap();
+        assert_eq!(padded[..12], [0u8; 12]);
+        assert_eq!(padded[30..], [0xde, 0xad]);
     }
-    None
-}
\ No newline at end of file
+
+    #[test]
+    fn rejects_malformed_evm_addresses() {
+        assert!(pad_evm_address("0xdead").is_err());
+        assert!(pad_evm_address("0xnot-hex").is_err());
+        assert!(pad_evm_address(&format!("0x{}", "00".repeat(32))).is_err());
+    }
+}